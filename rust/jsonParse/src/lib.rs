@@ -0,0 +1,637 @@
+#![type_length_limit = "2582009"]
+
+use std::error::Error;
+use std::fmt;
+use std::iter;
+
+pub mod object;
+pub mod serialize;
+pub mod stream;
+
+pub use object::{DuplicateKeys, Object};
+
+use nom::{
+    branch::alt,
+    bytes::streaming::tag,
+    character::streaming::{char, none_of, one_of},
+    combinator::{cut, map, not, opt, verify},
+    error::{context, ErrorKind, ParseError, VerboseError, VerboseErrorKind},
+    multi::{count, many0, many1},
+    sequence::{delimited, preceded, terminated, tuple},
+    Err, IResult, InputIter, InputTake, Slice,
+};
+use std::ops::{RangeFrom, RangeTo};
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Clone, PartialEq)]
+pub enum Json {
+    JSObject(Object),
+    JSArray(Vec<Json>),
+    JSString(String),
+    JSNumber(f64),
+    JSTrue,
+    JSFalse,
+    JSNull,
+}
+
+// The error records what was being parsed at each level, so a failure can
+// be reported as what was expected at the innermost one.
+type ParseResult<I, O> = IResult<I, O, VerboseError<I>>;
+
+/// How deeply arrays and objects may nest unless `ParseOptions` says
+/// otherwise. The parser recurses once per level, and this many levels fit
+/// in the 2 MiB stack of a spawned thread even in a debug build.
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+/// How to parse a document. A key repeated within an object keeps the last
+/// value, and arrays and objects may nest `DEFAULT_MAX_DEPTH` deep, unless
+/// this says otherwise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseOptions {
+    duplicates: DuplicateKeys,
+    max_depth: usize,
+}
+
+impl ParseOptions {
+    pub fn new() -> Self {
+        ParseOptions { duplicates: DuplicateKeys::default(), max_depth: DEFAULT_MAX_DEPTH }
+    }
+
+    /// How to treat a key repeated within an object.
+    pub fn duplicate_keys(self, duplicates: DuplicateKeys) -> Self {
+        ParseOptions { duplicates, ..self }
+    }
+
+    /// How many arrays and objects may be open at once. A document that
+    /// nests deeper fails at the first bracket past the limit.
+    pub fn max_depth(self, max_depth: usize) -> Self {
+        ParseOptions { max_depth, ..self }
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        ParseOptions::new()
+    }
+}
+
+fn void_many0<I, P, O, E>(parser: P) -> impl Fn(I) -> IResult<I, (), E>
+where
+    P: Fn(I) -> IResult<I, O, E>,
+    I: Clone + PartialEq,
+    E: nom::error::ParseError<I>,
+{
+    map(many0(parser), |_| ())
+}
+
+// Whitespace never waits for more input. Wherever it can end, whatever has
+// to come next decides whether the input is incomplete, so a value that
+// closes itself is complete as soon as it is closed.
+fn ws<I>(i: I) -> ParseResult<I, ()>
+where
+    I: InputIter<Item = char> + Slice<RangeFrom<usize>> + PartialEq + Copy,
+{
+    void_many0(nom::character::complete::one_of("\x09\x0A\x0D\x20"))(i)
+}
+
+fn token<I, P, O>(parser: P) -> impl Fn(I) -> ParseResult<I, O>
+where
+    P: Fn(I) -> ParseResult<I, O>,
+    I: InputIter<Item = char> + Slice<RangeFrom<usize>> + PartialEq + Copy,
+{
+    let parse = map(tuple((parser, ws)), |(result, _)| result);
+    move |stream| parse(stream)
+}
+
+const DIGITS: &str = "0123456789";
+
+fn digits<I>(input: I) -> ParseResult<I, String>
+where
+    I: InputIter<Item = char> + Slice<RangeFrom<usize>> + PartialEq + Copy,
+{
+    map(many1(one_of(DIGITS)), |digits| digits.into_iter().collect())(input)
+}
+
+// number = [ "-" ] int [ frac ] [ exp ], from RFC 8259. A leading zero may not
+// be followed by more digits, and once a "." or "e" has been seen, digits are
+// required, so "01", "1." and "1e" fail rather than parsing a prefix. Values
+// too large for an f64 are rejected rather than becoming infinite.
+fn number<I>(input: I) -> ParseResult<I, f64>
+where
+    I: InputIter<Item = char> + Slice<RangeFrom<usize>> + Slice<RangeTo<usize>> + PartialEq + Copy,
+{
+    let minus = map(opt(char('-')), |sign| if sign.is_some() { "-" } else { "" });
+    let int = alt((
+        map(char('0'), |_| String::from("0")),
+        map(
+            tuple((one_of("123456789"), many0(one_of(DIGITS)))),
+            |(first, rest)| iter::once(first).chain(rest).collect(),
+        ),
+    ));
+    let int = terminated(int, not(one_of(DIGITS)));
+    let frac = opt(preceded(char('.'), cut(context("a digit", digits))));
+    let exp = opt(tuple((one_of("eE"), opt(one_of("+-")), cut(context("a digit", digits)))));
+
+    let (rest, (sign, int, frac, exp)) = tuple((minus, int, frac, exp))(input)?;
+    let mut text = format!("{}{}", sign, int);
+    if let Some(frac) = frac {
+        text.push('.');
+        text.push_str(&frac);
+    }
+    if let Some((_, exp_sign, exp)) = exp {
+        text.push('e');
+        text.extend(exp_sign);
+        text.push_str(&exp);
+    }
+    // The standard library's conversion is correctly rounded.
+    match text.parse::<f64>() {
+        Ok(value) if value.is_finite() => Ok((rest, value)),
+        _ => {
+            let error = ParseError::from_error_kind(input, ErrorKind::Float);
+            Err(Err::Failure(ParseError::add_context(input, "a number within range", error)))
+        }
+    }
+}
+
+// Four hex digits, the payload of a \u escape.
+fn hex4<I>(input: I) -> ParseResult<I, u32>
+where
+    I: InputIter<Item = char> + Slice<RangeFrom<usize>> + PartialEq + Copy,
+{
+    let hex = context("four hex digits", count(one_of("0123456789abcdefABCDEF"), 4));
+    map(hex, |digits| {
+        digits.iter().fold(0, |code, digit| code * 16 + digit.to_digit(16).unwrap())
+    })(input)
+}
+
+// The rest of a \u escape. Characters outside the basic multilingual plane
+// are written as a UTF-16 surrogate pair, `\ud83d\ude00`, which decodes to one
+// character. A surrogate that is not part of such a pair is an error.
+fn unicode_escape<I>(input: I) -> ParseResult<I, char>
+where
+    I: InputIter<Item = char> + Slice<RangeFrom<usize>> + PartialEq + Copy,
+{
+    let lone_surrogate = |expected| {
+        let error = ParseError::from_error_kind(input, ErrorKind::Verify);
+        Err(Err::Failure(ParseError::add_context(input, expected, error)))
+    };
+    let (rest, high) = hex4(input)?;
+    let (rest, code) = match high {
+        0xD800..=0xDBFF => {
+            let low_escape = preceded(tuple((char('\\'), char('u'))), hex4);
+            let (rest, low) = context("a low surrogate", low_escape)(rest)?;
+            if !(0xDC00..=0xDFFF).contains(&low) {
+                return lone_surrogate("a low surrogate");
+            }
+            (rest, 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00))
+        }
+        0xDC00..=0xDFFF => return lone_surrogate("a high surrogate before a low one"),
+        _ => (rest, high),
+    };
+    Ok((rest, std::char::from_u32(code).unwrap()))
+}
+
+// string = quotation-mark *char quotation-mark, from RFC 8259. Control
+// characters must be escaped, and once a backslash or an opening quote has
+// been seen, a bad escape or a missing closing quote fails outright.
+fn string<I>(input: I) -> ParseResult<I, String>
+where
+    I: InputIter<Item = char> + Slice<RangeFrom<usize>> + Slice<RangeTo<usize>> + PartialEq + Copy,
+{
+    let unescaped = verify(none_of("\"\\"), |c: &char| *c >= '\u{20}');
+    let simple_escape = map(one_of("\"\\/bfnrt"), |c| match c {
+        'b' => '\u{8}',
+        'f' => '\u{c}',
+        'n' => '\n',
+        'r' => '\r',
+        't' => '\t',
+        c => c,
+    });
+    let escape = preceded(
+        char('\\'),
+        cut(context("an escape sequence", alt((simple_escape, preceded(char('u'), unicode_escape))))),
+    );
+    let close = cut(context("a closing '\"'", char('"')));
+    map(
+        delimited(char('"'), many0(alt((unescaped, escape))), close),
+        |chars| chars.into_iter().collect(),
+    )(input)
+}
+
+// A member, with where its key starts.
+fn member<I>(input: I, options: ParseOptions, depth: usize) -> ParseResult<I, (I, String, Json)>
+where
+    I: InputTake
+        + InputIter<Item = char>
+        + nom::Compare<&'static str>
+        + Slice<RangeFrom<usize>>
+        + Slice<RangeTo<usize>>
+        + PartialEq
+        + Copy,
+{
+    let value = move |input| json_value(input, options, depth + 1);
+    let member = tuple((token(string), cut(context("':'", token(char(':')))), cut(value)));
+    map(member, |(key, _, value)| (input, key, value))(input)
+}
+
+// Once an opening bracket or a separator has been seen, whatever follows has
+// to be right, so errors are reported where they happen rather than at the
+// start of the enclosing value.
+fn object<I>(input: I, options: ParseOptions, depth: usize) -> ParseResult<I, Object>
+where
+    I: InputTake
+        + InputIter<Item = char>
+        + nom::Compare<&'static str>
+        + Slice<RangeFrom<usize>>
+        + Slice<RangeTo<usize>>
+        + PartialEq
+        + Copy,
+{
+    // Each member is added as soon as it has been read, so a repeated key
+    // is reported without waiting for the rest of the object.
+    let next_member = move |input| member(input, options, depth);
+    let first = preceded(
+        token(char('{')),
+        cut(context("a string or '}'", alt((map(token(char('}')), |_| None), map(next_member, Some))))),
+    );
+    let more = cut(context(
+        "',' or '}'",
+        alt((
+            map(token(char('}')), |_| None),
+            map(preceded(token(char(',')), cut(context("a string", next_member))), Some),
+        )),
+    ));
+    let (mut rest, mut next) = first(input)?;
+    let mut object = Object::new();
+    while let Some((key_start, key, value)) = next {
+        if object.add(key, value, options.duplicates).is_err() {
+            let error = ParseError::from_error_kind(key_start, ErrorKind::Verify);
+            return Err(Err::Failure(ParseError::add_context(key_start, "a unique key", error)));
+        }
+        let (after, member) = more(rest)?;
+        rest = after;
+        next = member;
+    }
+    Ok((rest, object))
+}
+
+fn array<I>(input: I, options: ParseOptions, depth: usize) -> ParseResult<I, Vec<Json>>
+where
+    I: InputTake
+        + InputIter<Item = char>
+        + nom::Compare<&'static str>
+        + Slice<RangeFrom<usize>>
+        + Slice<RangeTo<usize>>
+        + PartialEq
+        + Copy,
+{
+    let value = move |input| json_value(input, options, depth + 1);
+    let elements = map(
+        tuple((value, many0(preceded(token(char(',')), cut(value))))),
+        |(first, rest)| iter::once(first).chain(rest).collect(),
+    );
+    let array = preceded(
+        token(char('[')),
+        cut(alt((
+            map(token(char(']')), |_| Vec::new()),
+            terminated(elements, context("',' or ']'", token(char(']')))),
+        ))),
+    );
+    array(input)
+}
+
+fn json_value<I>(input: I, options: ParseOptions, depth: usize) -> ParseResult<I, Json>
+where
+    I: InputTake
+        + InputIter<Item = char>
+        + nom::Compare<&'static str>
+        + Slice<RangeFrom<usize>>
+        + Slice<RangeTo<usize>>
+        + PartialEq
+        + Copy,
+{
+    // Stop at the bracket that would go too deep, rather than recursing
+    // until the stack runs out.
+    if depth >= options.max_depth {
+        if let Some('[') | Some('{') = input.iter_elements().next() {
+            let error = ParseError::from_error_kind(input, ErrorKind::TooLarge);
+            return Err(Err::Failure(ParseError::add_context(input, "less nesting", error)));
+        }
+    }
+
+    let js_true = map(token(tag("true")), |_: I| Json::JSTrue);
+    let js_false = map(token(tag("false")), |_: I| Json::JSFalse);
+    let js_null = map(token(tag("null")), |_: I| Json::JSNull);
+    let js_number = map(token(number), Json::JSNumber);
+
+    let string = token(string);
+    let js_string = map(&string, Json::JSString);
+
+    let js_object = map(move |input| object(input, options, depth), Json::JSObject);
+    let js_array = map(move |input| array(input, options, depth), Json::JSArray);
+
+    let value = token(context(
+        "a value",
+        alt((js_object, js_array, js_string, js_number, js_true, js_false, js_null)),
+    ));
+
+    value(input)
+}
+
+/// One value at the start of `input`, with the rest of the input. The parser
+/// is streaming, so a value cut off by the end of the input, or a number
+/// that might have more digits to come, asks for more input.
+pub fn parse_json_value<I>(input: I) -> ParseResult<I, Json>
+where
+    I: InputTake
+        + InputIter<Item = char>
+        + nom::Compare<&'static str>
+        + Slice<RangeFrom<usize>>
+        + Slice<RangeTo<usize>>
+        + PartialEq
+        + Copy,
+{
+    json_value(input, ParseOptions::default(), 0)
+}
+
+/// Why a document failed to parse. The line and column count from 1, and
+/// the column counts characters rather than bytes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonError {
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+}
+
+impl JsonError {
+    fn at(input: &str, offset: usize, expected: String) -> Self {
+        let before = &input[..offset];
+        let line_start = before.rfind('\n').map_or(0, |newline| newline + 1);
+        JsonError {
+            offset,
+            line: before.matches('\n').count() + 1,
+            column: before[line_start..].chars().count() + 1,
+            expected,
+        }
+    }
+
+    // `text` is what was parsed: `input`, perhaps with an end marker after it.
+    fn from_parse(input: &str, text: &str, error: &VerboseError<&str>) -> Self {
+        let rest = error.errors.first().map_or("", |&(rest, _)| rest);
+        let offset = (text.len() - rest.len()).min(input.len());
+        JsonError::at(input, offset, expected(error))
+    }
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "expected {} at line {}, column {}", self.expected, self.line, self.column)
+    }
+}
+
+impl Error for JsonError {}
+
+// The innermost context, or failing that the character that was wanted.
+fn expected(error: &VerboseError<&str>) -> String {
+    let context = error.errors.iter().find_map(|(_, kind)| match kind {
+        VerboseErrorKind::Context(context) => Some(context.to_string()),
+        _ => None,
+    });
+    let character = || {
+        error.errors.iter().find_map(|(_, kind)| match kind {
+            VerboseErrorKind::Char(c) => Some(format!("'{}'", c)),
+            _ => None,
+        })
+    };
+    context.or_else(character).unwrap_or_else(|| String::from("valid JSON"))
+}
+
+/// Parses a whole document: one value with optional whitespace around it,
+/// with the default `ParseOptions`.
+pub fn parse_json(input: &str) -> Result<Json, JsonError> {
+    parse_json_with(input, ParseOptions::default())
+}
+
+pub fn parse_json_with(input: &str, options: ParseOptions) -> Result<Json, JsonError> {
+    // The parsers are streaming and would ask for more input at the end of
+    // the document. A NUL can't appear in valid JSON outside an escape, so
+    // it marks the end without being mistaken for part of the value.
+    let text = format!("{}\0", input);
+    let offset = |rest: &str| (text.len() - rest.len()).min(input.len());
+    let parsed = preceded(ws, move |input| json_value(input, options, 0))(text.as_str());
+    match parsed {
+        Ok(("\0", json)) => Ok(json),
+        Ok((rest, _)) => Err(JsonError::at(input, offset(rest), String::from("end of input"))),
+        Err(Err::Error(error)) | Err(Err::Failure(error)) => Err(JsonError::from_parse(input, &text, &error)),
+        Err(Err::Incomplete(_)) => Err(JsonError::at(input, input.len(), String::from("more input"))),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // The parsers are streaming, so a number at the very end of the input is
+    // incomplete: there might be more digits to come. The tests mark the end
+    // of the value with a `#`.
+    fn value(input: &str) -> Option<Json> {
+        match parse_json_value(&*format!("{}#", input)) {
+            Ok(("#", value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    fn num(input: &str) -> Option<f64> {
+        match value(&format!("[{}]", input)) {
+            Some(Json::JSArray(values)) => match values.as_slice() {
+                [Json::JSNumber(n)] => Some(*n),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn numbers() {
+        assert_eq!(num("0"), Some(0.0));
+        assert_eq!(num("-0").map(f64::is_sign_negative), Some(true));
+        assert_eq!(num("123"), Some(123.0));
+        assert_eq!(num("-1.5"), Some(-1.5));
+        assert_eq!(num("2.5e3"), Some(2500.0));
+        assert_eq!(num("2.5E+3"), Some(2500.0));
+        assert_eq!(num("25e-1"), Some(2.5));
+        assert_eq!(num("0.000001"), Some(1e-6));
+        assert_eq!(number("12 "), Ok((" ", 12.0)));
+        assert_eq!(number("12"), Err(Err::Incomplete(nom::Needed::Size(1))));
+        assert_eq!(value("[1, -2.5, 3e2] "), Some(Json::JSArray(vec![
+            Json::JSNumber(1.0), Json::JSNumber(-2.5), Json::JSNumber(300.0),
+        ])));
+    }
+
+    #[test]
+    fn malformed_numbers() {
+        for bad in &["01", "-01", "00", "1.", "1.e5", ".5", "+1", "-", "1e", "1e+", "0x10", "- 1", "1.5.2"] {
+            assert_eq!(num(bad), None, "{}", bad);
+        }
+        assert_eq!(num("1e309"), None);
+        assert_eq!(num("-1e309"), None);
+    }
+
+    #[test]
+    fn number_edge_values() {
+        assert_eq!(num("1.7976931348623157e308"), Some(f64::MAX));
+        assert_eq!(num("-1.7976931348623157e308"), Some(f64::MIN));
+        assert_eq!(num("2.2250738585072014e-308"), Some(f64::MIN_POSITIVE));
+        assert_eq!(num("5e-324"), Some(f64::from_bits(1)));
+        assert_eq!(num("2e-324"), Some(0.0));
+        assert_eq!(num("1e-400"), Some(0.0));
+        // 2^53 + 1 is halfway between two f64s and rounds to even.
+        assert_eq!(num("9007199254740993"), Some(9_007_199_254_740_992.0));
+        assert_eq!(num("9007199254740995"), Some(9_007_199_254_740_996.0));
+        assert_eq!(num("0.1"), Some(0.1));
+        assert_eq!(num("0.30000000000000004"), Some(0.1 + 0.2));
+        assert_eq!(
+            num("3.141592653589793238462643383279502884197169399375105820974944"),
+            Some(std::f64::consts::PI),
+        );
+    }
+
+    fn text(input: &str) -> Option<String> {
+        match value(input) {
+            Some(Json::JSString(text)) => Some(text),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn string_escapes() {
+        assert_eq!(text(r#""plain""#), Some("plain".to_string()));
+        assert_eq!(text(r#""""#), Some(String::new()));
+        assert_eq!(
+            text(r#""a\"b\\c\/d\be\ff\ng\rh\ti""#),
+            Some("a\"b\\c/d\u{8}e\u{c}f\ng\rh\ti".to_string()),
+        );
+        assert_eq!(text(r#""caf\u00e9""#), Some("café".to_string()));
+        assert_eq!(text("\"café\""), Some("café".to_string()));
+        assert_eq!(text(r#""\u0000\u001F\u20ac""#), Some("\u{0}\u{1f}€".to_string()));
+        assert_eq!(text("\"\u{7f}\""), Some("\u{7f}".to_string()));
+        assert_eq!(
+            value(r#"{"key": "\"quoted\""}"#),
+            Some(Json::JSObject(
+                iter::once(("key".to_string(), Json::JSString("\"quoted\"".to_string()))).collect(),
+            )),
+        );
+    }
+
+    #[test]
+    fn surrogate_pairs() {
+        assert_eq!(text(r#""\ud83d\ude00""#), Some("😀".to_string()));
+        assert_eq!(text(r#""\uD83D\uDE00!""#), Some("😀!".to_string()));
+        assert_eq!(text(r#""\udbff\udfff""#), Some("\u{10ffff}".to_string()));
+        for lone in &[r#""\ud83d""#, r#""\ud83dx""#, r#""\ude00""#, r#""\ud83dA""#, r#""\ud83d\n""#, r#""\ude00\ud83d""#] {
+            assert_eq!(text(lone), None, "{}", lone);
+        }
+        assert!(matches!(string(r##""\ude00"#"##), Err(Err::Failure(_))));
+    }
+
+    #[test]
+    fn malformed_strings() {
+        for bad in &[r#""\x""#, r#""\u12""#, r#""\u12g4""#, r#""\U0041""#, r#""\'""#, "\"a\nb\"", "\"\t\"", "\"\u{1f}\"", "\"open"] {
+            assert_eq!(text(bad), None, "{:?}", bad);
+        }
+        assert!(matches!(string("\"a\u{0}b\"#"), Err(Err::Failure(_))));
+        assert_eq!(string("\"open"), Err(Err::Incomplete(nom::Needed::Size(1))));
+    }
+
+    fn error(input: &str) -> (usize, usize, usize, String) {
+        let error = parse_json(input).unwrap_err();
+        (error.offset, error.line, error.column, error.expected)
+    }
+
+    #[test]
+    fn whole_documents() {
+        assert_eq!(parse_json("true"), Ok(Json::JSTrue));
+        assert_eq!(parse_json("-12.5"), Ok(Json::JSNumber(-12.5)));
+        assert_eq!(parse_json(r#""text""#), Ok(Json::JSString("text".to_string())));
+        assert_eq!(
+            parse_json(" \n\t[1, {\"a\": null}, []] \r\n"),
+            Ok(Json::JSArray(vec![
+                Json::JSNumber(1.0),
+                Json::JSObject(iter::once(("a".to_string(), Json::JSNull)).collect()),
+                Json::JSArray(vec![]),
+            ])),
+        );
+        assert_eq!(parse_json("{ }"), Ok(Json::JSObject(Object::new())));
+    }
+
+    #[test]
+    fn error_positions() {
+        let expected = |offset, line, column, message: &str| (offset, line, column, message.to_string());
+        assert_eq!(error(""), expected(0, 1, 1, "a value"));
+        assert_eq!(error("   "), expected(3, 1, 4, "a value"));
+        assert_eq!(error("1 2"), expected(2, 1, 3, "end of input"));
+        assert_eq!(error("truex"), expected(4, 1, 5, "end of input"));
+        assert_eq!(error("[1,]"), expected(3, 1, 4, "a value"));
+        assert_eq!(error("[1,"), expected(3, 1, 4, "a value"));
+        assert_eq!(error("[1 2]"), expected(3, 1, 4, "',' or ']'"));
+        assert_eq!(error("{1}"), expected(1, 1, 2, "a string or '}'"));
+        assert_eq!(error(r#"{"a" 1}"#), expected(5, 1, 6, "':'"));
+        assert_eq!(error(r#"{"a": 1,}"#), expected(8, 1, 9, "a string"));
+        assert_eq!(error(r#"{"a": 1 "b": 2}"#), expected(8, 1, 9, "',' or '}'"));
+        assert_eq!(error("{\n  \"a\": tru\n}"), expected(9, 2, 8, "a value"));
+        assert_eq!(error("[\"é\", x]"), expected(7, 1, 7, "a value"));
+        assert_eq!(error(r#""abc"#), expected(4, 1, 5, "a closing '\"'"));
+        assert_eq!(error(r#""\q""#), expected(2, 1, 3, "an escape sequence"));
+        assert_eq!(error(r#""\u12x4""#), expected(5, 1, 6, "four hex digits"));
+        assert_eq!(error(r#""\ud83d""#), expected(7, 1, 8, "a low surrogate"));
+        assert_eq!(error("[1.]"), expected(3, 1, 4, "a digit"));
+        assert_eq!(error("1e400"), expected(0, 1, 1, "a number within range"));
+        assert_eq!(
+            parse_json(r#"{"a" 1}"#).unwrap_err().to_string(),
+            "expected ':' at line 1, column 6",
+        );
+    }
+
+    #[test]
+    fn object_key_order() {
+        let json = parse_json(r#"{"zebra": 1, "apple": {"y": 2, "x": 3}, "mango": 4}"#).unwrap();
+        assert_eq!(
+            serialize::Format::compact().to_string(&json).unwrap(),
+            r#"{"zebra":1,"apple":{"y":2,"x":3},"mango":4}"#,
+        );
+    }
+
+    #[test]
+    fn duplicate_keys() {
+        let document = r#"{"a": 1, "b": [{"c": 2, "c": 3}], "a": 4}"#;
+        let compact = |duplicates| -> Result<String, JsonError> {
+            let json = parse_json_with(document, ParseOptions::new().duplicate_keys(duplicates))?;
+            Ok(serialize::Format::compact().to_string(&json).unwrap())
+        };
+        assert_eq!(compact(DuplicateKeys::LastWins), Ok(r#"{"a":4,"b":[{"c":3}]}"#.to_string()));
+        assert_eq!(compact(DuplicateKeys::FirstWins), Ok(r#"{"a":1,"b":[{"c":2}]}"#.to_string()));
+        assert_eq!(
+            compact(DuplicateKeys::KeepAll),
+            Ok(r#"{"a":1,"b":[{"c":2,"c":3}],"a":4}"#.to_string()),
+        );
+        let error = compact(DuplicateKeys::Error).unwrap_err();
+        assert_eq!((error.offset, error.expected.as_str()), (24, "a unique key"));
+        assert_eq!(parse_json(document), parse_json_with(document, ParseOptions::new().duplicate_keys(DuplicateKeys::LastWins)));
+    }
+
+    #[test]
+    fn nesting_limit() {
+        let nested = |depth| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        assert!(parse_json(&nested(DEFAULT_MAX_DEPTH)).is_ok());
+        let error = parse_json(&nested(DEFAULT_MAX_DEPTH + 1)).unwrap_err();
+        assert_eq!((error.offset, error.expected.as_str()), (DEFAULT_MAX_DEPTH, "less nesting"));
+        assert_eq!(parse_json(&"[".repeat(50_000)).unwrap_err().offset, DEFAULT_MAX_DEPTH);
+
+        let document = r#"{"a": [{"b": 1}], "c": 2}"#;
+        let shallow = |max_depth| parse_json_with(document, ParseOptions::new().max_depth(max_depth));
+        assert!(shallow(3).is_ok());
+        assert_eq!(shallow(2).unwrap_err().offset, 7);
+        assert_eq!(shallow(0).unwrap_err().offset, 0);
+        assert_eq!(parse_json_with("1", ParseOptions::new().max_depth(0)), Ok(Json::JSNumber(1.0)));
+    }
+}
@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::iter::FromIterator;
+use std::mem;
+use std::slice;
+use std::vec;
+
+use super::Json;
+
+/// What to do when an object being parsed repeats a key.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicateKeys {
+    /// Reject the document.
+    Error,
+    /// Keep the first value and ignore the rest.
+    FirstWins,
+    /// Keep the last value, in the place of the first.
+    #[default]
+    LastWins,
+    /// Keep every member, in the order written.
+    KeepAll,
+}
+
+/// The error for adding a key that is already present when duplicates are an
+/// error.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DuplicateKey(pub String);
+
+impl fmt::Display for DuplicateKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "duplicate key {:?}", self.0)
+    }
+}
+
+impl Error for DuplicateKey {}
+
+/// The members of a JSON object in the order they were written, so that a
+/// document can be written back out the way it came in. An index from each
+/// key to its last member makes `get` and `insert` constant time. Only
+/// `get_all`, which matters when a key repeats, walks the members.
+///
+/// Two objects are equal if they have the same members in any order. A key
+/// that appears more than once has to have the same values in the same
+/// order in both.
+#[derive(Debug, Clone, Default)]
+pub struct Object {
+    members: Vec<(String, Json)>,
+    index: HashMap<String, usize>,
+}
+
+impl Object {
+    pub fn new() -> Self {
+        Object { members: Vec::new(), index: HashMap::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.members.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.members.is_empty()
+    }
+
+    /// The value of the last member with the given key.
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        self.index.get(key).map(|&i| &self.members[i].1)
+    }
+
+    /// The values of every member with the given key, in order.
+    pub fn get_all<'a>(&'a self, key: &'a str) -> impl DoubleEndedIterator<Item = &'a Json> + 'a {
+        self.members.iter().filter(move |(k, _)| k == key).map(|(_, value)| value)
+    }
+
+    /// Sets the value of `key`. The last member with that key keeps its place
+    /// and its old value is returned; otherwise the member goes at the end.
+    pub fn insert(&mut self, key: String, value: Json) -> Option<Json> {
+        match self.index.get(&key) {
+            Some(&i) => Some(mem::replace(&mut self.members[i].1, value)),
+            None => {
+                self.push(key, value);
+                None
+            }
+        }
+    }
+
+    /// Adds a member at the end, even if the key is already present.
+    pub fn push(&mut self, key: String, value: Json) {
+        self.index.insert(key.clone(), self.members.len());
+        self.members.push((key, value));
+    }
+
+    /// Adds a member as `duplicates` says to. Fails, leaving the object as it
+    /// was, if the key is already present and duplicates are an error.
+    pub fn add(&mut self, key: String, value: Json, duplicates: DuplicateKeys) -> Result<(), DuplicateKey> {
+        if !self.index.contains_key(&key) {
+            self.push(key, value);
+            return Ok(());
+        }
+        match duplicates {
+            DuplicateKeys::Error => return Err(DuplicateKey(key)),
+            DuplicateKeys::FirstWins => {}
+            DuplicateKeys::LastWins => {
+                self.insert(key, value);
+            }
+            DuplicateKeys::KeepAll => self.push(key, value),
+        }
+        Ok(())
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        Iter(self.members.iter())
+    }
+}
+
+impl PartialEq for Object {
+    fn eq(&self, other: &Self) -> bool {
+        // Each side's values grouped by key, in order.
+        fn grouped(object: &Object) -> HashMap<&str, Vec<&Json>> {
+            let mut groups: HashMap<_, Vec<_>> = HashMap::with_capacity(object.index.len());
+            for (key, value) in object {
+                groups.entry(key.as_str()).or_default().push(value);
+            }
+            groups
+        }
+        self.len() == other.len() && self.index.len() == other.index.len() && grouped(self) == grouped(other)
+    }
+}
+
+/// Collects like a map: a repeated key replaces the earlier value.
+impl FromIterator<(String, Json)> for Object {
+    fn from_iter<T: IntoIterator<Item = (String, Json)>>(members: T) -> Self {
+        let mut object = Object::new();
+        for (key, value) in members {
+            object.insert(key, value);
+        }
+        object
+    }
+}
+
+pub struct Iter<'a>(slice::Iter<'a, (String, Json)>);
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (&'a String, &'a Json);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(|(key, value)| (key, value))
+    }
+}
+
+impl<'a> IntoIterator for &'a Object {
+    type Item = (&'a String, &'a Json);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl IntoIterator for Object {
+    type Item = (String, Json);
+    type IntoIter = vec::IntoIter<(String, Json)>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.members.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn number(n: f64) -> Json {
+        Json::JSNumber(n)
+    }
+
+    #[test]
+    fn keeps_insertion_order() {
+        let mut object = Object::new();
+        for key in &["zebra", "apple", "mango"] {
+            object.insert(key.to_string(), Json::JSNull);
+        }
+        assert_eq!(object.insert("apple".to_string(), Json::JSTrue), Some(Json::JSNull));
+        let keys: Vec<_> = object.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, ["zebra", "apple", "mango"]);
+        assert_eq!(object.get("apple"), Some(&Json::JSTrue));
+        assert_eq!(object.get("pear"), None);
+    }
+
+    #[test]
+    fn duplicate_policies() {
+        let members = [("a", 1.0), ("b", 2.0), ("a", 3.0)];
+        let build = |duplicates| {
+            let mut object = Object::new();
+            let accepted: Vec<_> = members
+                .iter()
+                .map(|&(key, n)| object.add(key.to_string(), number(n), duplicates).is_ok())
+                .collect();
+            let members: Vec<_> = object.iter().map(|(key, value)| (key.clone(), value.clone())).collect();
+            (accepted, members)
+        };
+        let member = |key: &str, n| (key.to_string(), number(n));
+
+        assert_eq!(build(DuplicateKeys::Error), (vec![true, true, false], vec![member("a", 1.0), member("b", 2.0)]));
+        assert_eq!(build(DuplicateKeys::FirstWins), (vec![true; 3], vec![member("a", 1.0), member("b", 2.0)]));
+        assert_eq!(build(DuplicateKeys::LastWins), (vec![true; 3], vec![member("a", 3.0), member("b", 2.0)]));
+        assert_eq!(
+            build(DuplicateKeys::KeepAll),
+            (vec![true; 3], vec![member("a", 1.0), member("b", 2.0), member("a", 3.0)]),
+        );
+
+        let mut object = Object::new();
+        object.push("a".to_string(), number(1.0));
+        assert_eq!(object.add("a".to_string(), number(2.0), DuplicateKeys::Error), Err(DuplicateKey("a".to_string())));
+        assert_eq!(object.get("a"), Some(&number(1.0)));
+    }
+
+    #[test]
+    fn equality_ignores_order() {
+        let object = |members: &[(&str, f64)]| {
+            let mut object = Object::new();
+            for &(key, n) in members {
+                object.push(key.to_string(), number(n));
+            }
+            object
+        };
+        assert_eq!(object(&[("a", 1.0), ("b", 2.0)]), object(&[("b", 2.0), ("a", 1.0)]));
+        assert_ne!(object(&[("a", 1.0), ("b", 2.0)]), object(&[("a", 1.0), ("b", 3.0)]));
+        assert_ne!(object(&[("a", 1.0)]), object(&[("a", 1.0), ("b", 2.0)]));
+        assert_eq!(object(&[("a", 1.0), ("b", 0.0), ("a", 2.0)]), object(&[("b", 0.0), ("a", 1.0), ("a", 2.0)]));
+        assert_ne!(object(&[("a", 1.0), ("a", 2.0)]), object(&[("a", 2.0), ("a", 1.0)]));
+        assert_ne!(object(&[("a", 1.0), ("a", 1.0)]), object(&[("a", 1.0), ("b", 1.0)]));
+    }
+}
@@ -0,0 +1,359 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+use super::Json;
+
+/// How a `Json` value is laid out as text. The compact layout has no
+/// whitespace at all. The pretty layout puts each array element and object
+/// member on its own line, indented by one `indent` per level of nesting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Format {
+    indent: Option<String>,
+    sort_keys: bool,
+}
+
+/// One level of indentation in the pretty layout. Only spaces and tabs are
+/// offered, since anything else between tokens would not be valid JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Indent {
+    Spaces(usize),
+    Tabs(usize),
+}
+
+#[derive(Debug)]
+pub enum WriteError {
+    /// JSON has no way to write NaN or an infinity.
+    NonFinite(f64),
+    Fmt(fmt::Error),
+    Io(io::Error),
+}
+
+impl fmt::Display for WriteError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WriteError::NonFinite(n) => write!(f, "{} cannot be written as a JSON number", n),
+            WriteError::Fmt(error) => error.fmt(f),
+            WriteError::Io(error) => error.fmt(f),
+        }
+    }
+}
+
+impl Error for WriteError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            WriteError::NonFinite(_) => None,
+            WriteError::Fmt(error) => Some(error),
+            WriteError::Io(error) => Some(error),
+        }
+    }
+}
+
+impl From<fmt::Error> for WriteError {
+    fn from(error: fmt::Error) -> Self {
+        WriteError::Fmt(error)
+    }
+}
+
+// Lets an `io::Write` stand in for a `fmt::Write`. `fmt::Error` carries
+// nothing, so the underlying `io::Error` is kept to one side.
+struct IoAdapter<W> {
+    inner: W,
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write> fmt::Write for IoAdapter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|error| {
+            self.error = Some(error);
+            fmt::Error
+        })
+    }
+}
+
+impl Format {
+    pub fn compact() -> Self {
+        Format { indent: None, sort_keys: false }
+    }
+
+    pub fn pretty(indent: Indent) -> Self {
+        let indent = match indent {
+            Indent::Spaces(width) => " ".repeat(width),
+            Indent::Tabs(width) => "\t".repeat(width),
+        };
+        Format { indent: Some(indent), sort_keys: false }
+    }
+
+    /// Write object members in key order rather than the order the object
+    /// holds them in.
+    pub fn sort_keys(self, sort_keys: bool) -> Self {
+        Format { sort_keys, ..self }
+    }
+
+    pub fn write<W: fmt::Write>(&self, json: &Json, out: &mut W) -> Result<(), WriteError> {
+        self.value(json, 0, out)
+    }
+
+    pub fn write_io<W: io::Write>(&self, json: &Json, out: W) -> Result<(), WriteError> {
+        let mut adapter = IoAdapter { inner: out, error: None };
+        self.write(json, &mut adapter).map_err(|error| match (error, adapter.error) {
+            (WriteError::Fmt(_), Some(error)) => WriteError::Io(error),
+            (error, _) => error,
+        })
+    }
+
+    pub fn to_string(&self, json: &Json) -> Result<String, WriteError> {
+        let mut out = String::new();
+        self.write(json, &mut out)?;
+        Ok(out)
+    }
+
+    fn newline<W: fmt::Write>(&self, depth: usize, out: &mut W) -> fmt::Result {
+        if let Some(indent) = &self.indent {
+            out.write_char('\n')?;
+            for _ in 0..depth {
+                out.write_str(indent)?;
+            }
+        }
+        Ok(())
+    }
+
+    // Writes `items` between `open` and `close`, separated by commas, with each
+    // item on its own line in the pretty layout.
+    fn sequence<T, W, F>(
+        &self,
+        (open, close): (char, char),
+        items: &[T],
+        depth: usize,
+        out: &mut W,
+        mut item: F,
+    ) -> Result<(), WriteError>
+    where
+        W: fmt::Write,
+        F: FnMut(&T, &mut W) -> Result<(), WriteError>,
+    {
+        out.write_char(open)?;
+        for (i, x) in items.iter().enumerate() {
+            if i > 0 {
+                out.write_char(',')?;
+            }
+            self.newline(depth + 1, out)?;
+            item(x, out)?;
+        }
+        if !items.is_empty() {
+            self.newline(depth, out)?;
+        }
+        out.write_char(close)?;
+        Ok(())
+    }
+
+    fn value<W: fmt::Write>(&self, json: &Json, depth: usize, out: &mut W) -> Result<(), WriteError> {
+        match json {
+            Json::JSObject(members) => {
+                let mut members: Vec<_> = members.iter().collect();
+                if self.sort_keys {
+                    members.sort_by_key(|&(key, _)| key);
+                }
+                let colon = if self.indent.is_some() { ": " } else { ":" };
+                self.sequence(('{', '}'), &members, depth, out, |(key, value), out| {
+                    write_string(key, out)?;
+                    out.write_str(colon)?;
+                    self.value(value, depth + 1, out)
+                })
+            }
+            Json::JSArray(values) => {
+                self.sequence(('[', ']'), values, depth, out, |value, out| self.value(value, depth + 1, out))
+            }
+            Json::JSString(s) => Ok(write_string(s, out)?),
+            Json::JSNumber(n) => write_number(*n, out),
+            Json::JSTrue => Ok(out.write_str("true")?),
+            Json::JSFalse => Ok(out.write_str("false")?),
+            Json::JSNull => Ok(out.write_str("null")?),
+        }
+    }
+}
+
+impl Default for Format {
+    fn default() -> Self {
+        Format::compact()
+    }
+}
+
+// Only the quote, the backslash and control characters need escaping.
+// Everything else, including non-ASCII text, is written as it is.
+fn write_string<W: fmt::Write>(s: &str, out: &mut W) -> fmt::Result {
+    out.write_char('"')?;
+    for c in s.chars() {
+        match c {
+            '"' => out.write_str("\\\"")?,
+            '\\' => out.write_str("\\\\")?,
+            '\n' => out.write_str("\\n")?,
+            '\r' => out.write_str("\\r")?,
+            '\t' => out.write_str("\\t")?,
+            '\u{8}' => out.write_str("\\b")?,
+            '\u{c}' => out.write_str("\\f")?,
+            c if c < '\u{20}' => write!(out, "\\u{:04x}", c as u32)?,
+            c => out.write_char(c)?,
+        }
+    }
+    out.write_char('"')
+}
+
+// The standard library prints the shortest digits that read back as the same
+// f64. Plain notation is used for everyday magnitudes and exponent notation
+// outside them, so 1e300 is not written out as 301 digits.
+fn write_number<W: fmt::Write>(n: f64, out: &mut W) -> Result<(), WriteError> {
+    if !n.is_finite() {
+        return Err(WriteError::NonFinite(n));
+    }
+    let magnitude = n.abs();
+    if magnitude == 0.0 || (1e-7..1e21).contains(&magnitude) {
+        write!(out, "{}", n)?;
+    } else {
+        write!(out, "{:e}", n)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::object::Object;
+    use crate::parse_json_value;
+
+    fn object(members: &[(&str, Json)]) -> Json {
+        Json::JSObject(members.iter().map(|(key, value)| (key.to_string(), value.clone())).collect())
+    }
+
+    fn parse(text: &str) -> Option<Json> {
+        match parse_json_value(&*format!("{}#", text)) {
+            Ok(("#", value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    // A xorshift generator, so the property tests are repeatable without
+    // pulling in a dependency.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+        fn below(&mut self, n: u64) -> u64 {
+            self.next() % n
+        }
+        fn number(&mut self) -> f64 {
+            loop {
+                let n = match self.below(3) {
+                    0 => self.below(2000) as f64 - 1000.0,
+                    1 => (self.below(2_000_000) as f64 - 1e6) / 1000.0,
+                    _ => f64::from_bits(self.next()),
+                };
+                if n.is_finite() {
+                    return n;
+                }
+            }
+        }
+        fn string(&mut self) -> String {
+            const SAMPLES: &[char] = &['a', 'Z', ' ', '"', '\\', '/', '\n', '\u{0}', '\u{1f}', '\u{7f}', 'é', '€', '\u{ffff}', '😀'];
+            (0..self.below(8)).map(|_| SAMPLES[self.below(SAMPLES.len() as u64) as usize]).collect()
+        }
+        fn json(&mut self, depth: u32) -> Json {
+            let kinds = if depth == 0 { 4 } else { 6 };
+            match self.below(kinds) {
+                0 => [Json::JSTrue, Json::JSFalse, Json::JSNull][self.below(3) as usize].clone(),
+                1 | 2 => Json::JSNumber(self.number()),
+                3 => Json::JSString(self.string()),
+                4 => Json::JSArray((0..self.below(4)).map(|_| self.json(depth - 1)).collect()),
+                _ => Json::JSObject((0..self.below(4)).map(|_| (self.string(), self.json(depth - 1))).collect()),
+            }
+        }
+    }
+
+    #[test]
+    fn compact_and_pretty() {
+        let json = object(&[
+            ("list", Json::JSArray(vec![Json::JSNumber(1.0), Json::JSNull, Json::JSArray(vec![])])),
+            ("empty", Json::JSObject(Object::new())),
+        ]);
+        let compact = Format::compact().sort_keys(true);
+        assert_eq!(compact.to_string(&json).unwrap(), r#"{"empty":{},"list":[1,null,[]]}"#);
+        let pretty = Format::pretty(Indent::Spaces(2)).sort_keys(true);
+        assert_eq!(
+            pretty.to_string(&json).unwrap(),
+            "{\n  \"empty\": {},\n  \"list\": [\n    1,\n    null,\n    []\n  ]\n}",
+        );
+        let tabs = Format::pretty(Indent::Tabs(1)).sort_keys(true);
+        assert_eq!(tabs.to_string(&object(&[("a", Json::JSTrue)])).unwrap(), "{\n\t\"a\": true\n}");
+        let flat = Format::pretty(Indent::Spaces(0));
+        assert_eq!(flat.to_string(&Json::JSArray(vec![Json::JSNull])).unwrap(), "[\nnull\n]");
+        assert_eq!(Format::default().to_string(&Json::JSFalse).unwrap(), "false");
+    }
+
+    #[test]
+    fn strings_are_escaped() {
+        let json = Json::JSString("q\"b\\s/\n\r\t\u{8}\u{c}\u{0}\u{1f}\u{7f}é😀".to_string());
+        assert_eq!(
+            Format::compact().to_string(&json).unwrap(),
+            r#""q\"b\\s/\n\r\t\b\f\u0000\u001f"#.to_string() + "\u{7f}é😀\"",
+        );
+    }
+
+    #[test]
+    fn numbers() {
+        let cases = [
+            (0.0, "0"), (-0.0, "-0"), (1.0, "1"), (-2.5, "-2.5"), (0.1, "0.1"),
+            (0.1 + 0.2, "0.30000000000000004"), (1e20, "100000000000000000000"),
+            (1e21, "1e21"), (1e-7, "0.0000001"), (1.5e-8, "1.5e-8"),
+            (f64::MAX, "1.7976931348623157e308"), (f64::from_bits(1), "5e-324"),
+        ];
+        for &(n, text) in &cases {
+            assert_eq!(Format::compact().to_string(&Json::JSNumber(n)).unwrap(), text);
+        }
+        for &n in &[f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let nested = Json::JSArray(vec![Json::JSNumber(1.0), Json::JSNumber(n)]);
+            assert!(matches!(Format::compact().to_string(&nested), Err(WriteError::NonFinite(_))));
+        }
+    }
+
+    #[test]
+    fn io_write() {
+        let json = object(&[("a", Json::JSArray(vec![Json::JSString("é".to_string())]))]);
+        let mut bytes = Vec::new();
+        Format::pretty(Indent::Spaces(1)).write_io(&json, &mut bytes).unwrap();
+        assert_eq!(String::from_utf8(bytes).unwrap(), Format::pretty(Indent::Spaces(1)).to_string(&json).unwrap());
+
+        struct Full;
+        impl io::Write for Full {
+            fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+                Err(io::Error::other("full"))
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+        assert!(matches!(Format::compact().write_io(&json, Full), Err(WriteError::Io(_))));
+    }
+
+    #[test]
+    fn round_trip() {
+        let formats = [
+            Format::compact(),
+            Format::compact().sort_keys(true),
+            Format::pretty(Indent::Spaces(2)),
+            Format::pretty(Indent::Tabs(1)).sort_keys(true),
+        ];
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+        for _ in 0..500 {
+            let json = rng.json(3);
+            for format in &formats {
+                let text = format.to_string(&json).unwrap();
+                assert_eq!(parse(&text), Some(json.clone()), "{}", text);
+            }
+        }
+    }
+}
@@ -0,0 +1,569 @@
+use std::str;
+
+use nom::Err;
+
+use super::{json_value, string, DuplicateKeys, Json, JsonError, Object, ParseOptions, ParseResult};
+
+/// What `JsonStreamParser::next_value` found.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Next {
+    Value(Json),
+    /// Everything so far is the start of a value, but the rest of it has not
+    /// arrived yet.
+    Incomplete,
+    /// The input has finished and every value in it has been read.
+    Done,
+}
+
+/// Parses a sequence of JSON values, separated by optional whitespace, from
+/// input that arrives in pieces. Each piece is handed over with `feed`, and
+/// `next_value` reads a value once all of it has arrived. Error positions are
+/// counted from the start of the whole stream.
+///
+/// The parser keeps its place within a value: the arrays and objects open so
+/// far are held as they are read, and a string is only searched for its end
+/// from where the last search stopped. Only a number or a `true`, `false` or
+/// `null` split between pieces is read again from its start.
+#[derive(Debug)]
+pub struct JsonStreamParser {
+    // The input from `start` on has not been read yet. Ends with a NUL once
+    // the input has finished. A NUL can't appear in valid JSON outside an
+    // escape, so it ends a number at the very end without being mistaken for
+    // part of it.
+    text: String,
+    start: usize,
+    // The start of a character split between two pieces.
+    partial: Vec<u8>,
+    // The bytes after `text` are not UTF-8, so nothing after them is read.
+    invalid: bool,
+    finished: bool,
+    options: ParseOptions,
+    open: Vec<Open>,
+    expect: Expect,
+    // How far into the string at `start` the search for its end has got,
+    // and whether it stopped just after a backslash.
+    scanned: usize,
+    escaped: bool,
+    // Nothing has arrived since `next_value` last needed more input.
+    waiting: bool,
+    failed: Option<JsonError>,
+    // Where `start` is in the stream.
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+// An array or object that has been opened but not yet closed.
+#[derive(Debug)]
+enum Open {
+    Array(Vec<Json>),
+    // The members so far, and a key still waiting for its value, with where
+    // the key starts.
+    Object(Object, Option<(String, JsonError)>),
+}
+
+// What can come next, given what is open.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Expect {
+    Value,
+    // Just after '[': an element or ']'.
+    FirstElement,
+    ElementEnd,
+    // Just after '{': a key or '}'.
+    FirstMember,
+    // A key, after a ',' in an object.
+    Key,
+    Colon,
+    MemberEnd,
+}
+
+impl JsonStreamParser {
+    pub fn new() -> Self {
+        JsonStreamParser {
+            text: String::new(),
+            start: 0,
+            partial: Vec::new(),
+            invalid: false,
+            finished: false,
+            options: ParseOptions::default(),
+            open: Vec::new(),
+            expect: Expect::Value,
+            scanned: 0,
+            escaped: false,
+            waiting: false,
+            failed: None,
+            offset: 0,
+            line: 1,
+            column: 1,
+        }
+    }
+
+    /// How to treat a key repeated within an object. The last value wins
+    /// unless this says otherwise.
+    pub fn duplicate_keys(self, duplicates: DuplicateKeys) -> Self {
+        JsonStreamParser { options: self.options.duplicate_keys(duplicates), ..self }
+    }
+
+    /// How many arrays and objects may be open at once within a value.
+    pub fn max_depth(self, max_depth: usize) -> Self {
+        JsonStreamParser { options: self.options.max_depth(max_depth), ..self }
+    }
+
+    pub fn feed(&mut self, bytes: &[u8]) {
+        self.waiting = false;
+        if self.invalid {
+            return;
+        }
+        if self.finished {
+            self.text.pop();
+        }
+        self.partial.extend_from_slice(bytes);
+        let valid = match str::from_utf8(&self.partial) {
+            Ok(text) => text.len(),
+            Err(error) => {
+                self.invalid = error.error_len().is_some();
+                error.valid_up_to()
+            }
+        };
+        self.text.push_str(str::from_utf8(&self.partial[..valid]).unwrap());
+        self.partial.drain(..valid);
+        if self.finished {
+            self.mark_end();
+        }
+    }
+
+    /// Marks the end of the input, after which a value at the very end of the
+    /// buffer is known to be complete.
+    pub fn finish(&mut self) {
+        if !self.finished {
+            self.finished = true;
+            self.waiting = false;
+            self.mark_end();
+        }
+    }
+
+    fn mark_end(&mut self) {
+        // A character can no longer be completed by the next piece.
+        self.invalid |= !self.partial.is_empty();
+        if !self.invalid {
+            self.text.push('\0');
+        }
+    }
+
+    /// The next value, or `Next::Incomplete` if more input is needed before
+    /// there can be one. After an error, asking again gives the same error.
+    pub fn next_value(&mut self) -> Result<Next, JsonError> {
+        if let Some(error) = &self.failed {
+            return Err(error.clone());
+        }
+        if self.waiting {
+            return Ok(Next::Incomplete);
+        }
+        let next = self.read();
+        // Drop what has been read once it is at least half of the buffer, so
+        // that each byte is moved a bounded number of times.
+        if self.start * 2 >= self.text.len() {
+            self.text.drain(..self.start);
+            self.start = 0;
+        }
+        match next {
+            Ok(Next::Incomplete) => self.waiting = true,
+            Err(ref error) => self.failed = Some(error.clone()),
+            _ => {}
+        }
+        next
+    }
+
+    fn read(&mut self) -> Result<Next, JsonError> {
+        loop {
+            let rest = &self.text[self.start..];
+            let whitespace = rest.len() - rest.trim_start_matches(&['\t', '\n', '\r', ' '][..]).len();
+            self.consume(whitespace);
+            let rest = &self.text[self.start..];
+            let first = match rest.chars().next() {
+                Some(first) => first,
+                None => return self.incomplete(),
+            };
+            if self.finished && rest == "\0" && self.open.is_empty() {
+                return Ok(Next::Done);
+            }
+            let json = match (self.expect, first) {
+                (Expect::FirstElement, ']') | (Expect::ElementEnd, ']') | (Expect::FirstMember, '}') | (Expect::MemberEnd, '}') => {
+                    self.consume(1);
+                    match self.open.pop() {
+                        Some(Open::Array(elements)) => Json::JSArray(elements),
+                        Some(Open::Object(object, _)) => Json::JSObject(object),
+                        None => continue,
+                    }
+                }
+                (Expect::ElementEnd, ',') => {
+                    self.consume(1);
+                    self.expect = Expect::Value;
+                    continue;
+                }
+                (Expect::MemberEnd, ',') => {
+                    self.consume(1);
+                    self.expect = Expect::Key;
+                    continue;
+                }
+                (Expect::Colon, ':') => {
+                    self.consume(1);
+                    self.expect = Expect::Value;
+                    continue;
+                }
+                (Expect::FirstMember, '"') | (Expect::Key, '"') => {
+                    let at = self.position(String::new());
+                    let key = match self.token(|input| string(input))? {
+                        Some(key) => key,
+                        None => return self.incomplete(),
+                    };
+                    if let Some(Open::Object(_, pending)) = self.open.last_mut() {
+                        *pending = Some((key, at));
+                    }
+                    self.expect = Expect::Colon;
+                    continue;
+                }
+                (Expect::Value, '[') | (Expect::Value, '{') | (Expect::FirstElement, '[') | (Expect::FirstElement, '{')
+                    if self.open.len() >= self.options.max_depth =>
+                {
+                    return Err(self.position(String::from("less nesting")));
+                }
+                (Expect::Value, '[') | (Expect::FirstElement, '[') => {
+                    self.consume(1);
+                    self.open.push(Open::Array(Vec::new()));
+                    self.expect = Expect::FirstElement;
+                    continue;
+                }
+                (Expect::Value, '{') | (Expect::FirstElement, '{') => {
+                    self.consume(1);
+                    self.open.push(Open::Object(Object::new(), None));
+                    self.expect = Expect::FirstMember;
+                    continue;
+                }
+                (Expect::Value, _) | (Expect::FirstElement, _) => {
+                    let (options, depth) = (self.options, self.open.len());
+                    match self.token(move |input| json_value(input, options, depth))? {
+                        Some(json) => json,
+                        None => return self.incomplete(),
+                    }
+                }
+                (Expect::ElementEnd, _) => return Err(self.position(String::from("',' or ']'"))),
+                (Expect::FirstMember, _) => return Err(self.position(String::from("a string or '}'"))),
+                (Expect::Key, _) => return Err(self.position(String::from("a string"))),
+                (Expect::Colon, _) => return Err(self.position(String::from("':'"))),
+                (Expect::MemberEnd, _) => return Err(self.position(String::from("',' or '}'"))),
+            };
+            if let Some(json) = self.complete(json)? {
+                return Ok(Next::Value(json));
+            }
+        }
+    }
+
+    // Adds a value that has been read to whatever is open around it. The
+    // value itself if nothing is.
+    fn complete(&mut self, json: Json) -> Result<Option<Json>, JsonError> {
+        match self.open.last_mut() {
+            None => {
+                self.expect = Expect::Value;
+                return Ok(Some(json));
+            }
+            Some(Open::Array(elements)) => {
+                elements.push(json);
+                self.expect = Expect::ElementEnd;
+            }
+            Some(Open::Object(object, pending)) => {
+                if let Some((key, at)) = pending.take() {
+                    if object.add(key, json, self.options.duplicates).is_err() {
+                        return Err(JsonError { expected: String::from("a unique key"), ..at });
+                    }
+                }
+                self.expect = Expect::MemberEnd;
+            }
+        }
+        Ok(None)
+    }
+
+    // Reads the string, number or literal at the start of the unread input.
+    // `None` if it may go on past the input that has arrived so far.
+    fn token<O, P>(&mut self, parse: P) -> Result<Option<O>, JsonError>
+    where
+        P: Fn(&str) -> ParseResult<&str, O>,
+    {
+        if self.text[self.start..].starts_with('"') && !self.string_ends() {
+            return Ok(None);
+        }
+        let text = &self.text[self.start..];
+        let input = match text.strip_suffix('\0') {
+            Some(input) if self.finished => input,
+            _ => text,
+        };
+        match parse(text) {
+            Ok((rest, token)) => {
+                let consumed = (text.len() - rest.len()).min(input.len());
+                self.consume(consumed);
+                Ok(Some(token))
+            }
+            Err(Err::Error(error)) | Err(Err::Failure(error)) => {
+                let error = JsonError::from_parse(input, text, &error);
+                Err(self.locate(error))
+            }
+            Err(Err::Incomplete(_)) => Ok(None),
+        }
+    }
+
+    // Whether the string at the start of the unread input can be parsed yet:
+    // its closing quote has arrived, or something that can't be part of it
+    // has. Carries on from where the last call stopped.
+    fn string_ends(&mut self) -> bool {
+        let from = self.scanned.max(1);
+        for (i, &byte) in self.text.as_bytes()[self.start + from..].iter().enumerate() {
+            if self.escaped {
+                self.escaped = false;
+                if !b"\"\\/bfnrtu".contains(&byte) {
+                    return true;
+                }
+            } else if byte == b'\\' {
+                self.escaped = true;
+            } else if byte == b'"' || byte < 0x20 {
+                return true;
+            }
+            self.scanned = from + i + 1;
+        }
+        false
+    }
+
+    fn incomplete(&self) -> Result<Next, JsonError> {
+        let end = self.text.len() - self.start;
+        if self.invalid {
+            let error = JsonError::at(&self.text[self.start..], end, String::from("valid UTF-8"));
+            Err(self.locate(error))
+        } else if self.finished {
+            let error = JsonError::at(&self.text[self.start..], end, String::from("more input"));
+            Err(self.locate(error))
+        } else {
+            Ok(Next::Incomplete)
+        }
+    }
+
+    // Where the unread input starts.
+    fn position(&self, expected: String) -> JsonError {
+        JsonError { offset: self.offset, line: self.line, column: self.column, expected }
+    }
+
+    // Moves a position in the unread input to its place in the stream.
+    fn locate(&self, error: JsonError) -> JsonError {
+        JsonError {
+            offset: self.offset + error.offset,
+            line: self.line + error.line - 1,
+            column: if error.line == 1 { self.column + error.column - 1 } else { error.column },
+            expected: error.expected,
+        }
+    }
+
+    fn consume(&mut self, bytes: usize) {
+        if bytes == 0 {
+            return;
+        }
+        let consumed = &self.text[self.start..self.start + bytes];
+        let end = self.locate(JsonError::at(consumed, bytes, String::new()));
+        self.offset = end.offset;
+        self.line = end.line;
+        self.column = end.column;
+        self.start += bytes;
+        self.scanned = 0;
+        self.escaped = false;
+    }
+}
+
+impl Default for JsonStreamParser {
+    fn default() -> Self {
+        JsonStreamParser::new()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::parse_json;
+
+    fn values(parser: &mut JsonStreamParser) -> Result<Vec<Json>, JsonError> {
+        let mut values = Vec::new();
+        while let Next::Value(json) = parser.next_value()? {
+            values.push(json);
+        }
+        Ok(values)
+    }
+
+    #[test]
+    fn byte_at_a_time() {
+        let document = "{\"a\": [1, 2.5e1, \"x\\u00e9é\"]} [true]12 \"😀\" null";
+        let expected = vec![
+            Json::JSObject(std::iter::once((
+                "a".to_string(),
+                Json::JSArray(vec![Json::JSNumber(1.0), Json::JSNumber(25.0), Json::JSString("xéé".to_string())]),
+            )).collect()),
+            Json::JSArray(vec![Json::JSTrue]),
+            Json::JSNumber(12.0),
+            Json::JSString("😀".to_string()),
+            Json::JSNull,
+        ];
+        let mut parser = JsonStreamParser::new();
+        let mut found = Vec::new();
+        for byte in document.bytes() {
+            parser.feed(&[byte]);
+            found.extend(values(&mut parser).unwrap());
+        }
+        // `null` closes itself, so it is read before the input finishes.
+        assert_eq!(found, expected);
+        assert_eq!(parser.next_value(), Ok(Next::Incomplete));
+        parser.finish();
+        assert_eq!(parser.next_value(), Ok(Next::Done));
+    }
+
+    #[test]
+    fn numbers_wait_for_the_end() {
+        let mut parser = JsonStreamParser::new();
+        parser.feed(b" 42");
+        assert_eq!(parser.next_value(), Ok(Next::Incomplete));
+        parser.feed(b"0");
+        assert_eq!(parser.next_value(), Ok(Next::Incomplete));
+        parser.finish();
+        assert_eq!(parser.next_value(), Ok(Next::Value(Json::JSNumber(420.0))));
+        assert_eq!(parser.next_value(), Ok(Next::Done));
+
+        let mut empty = JsonStreamParser::new();
+        assert_eq!(empty.next_value(), Ok(Next::Incomplete));
+        empty.feed(b" \n");
+        empty.finish();
+        assert_eq!(empty.next_value(), Ok(Next::Done));
+    }
+
+    #[test]
+    fn incomplete_is_not_invalid() {
+        let mut parser = JsonStreamParser::new();
+        for piece in &["[1, ", "\"ab", "\\u00", "e9", "\\ud83d", "\\", "ude00", "\"", ", tr", "u"] {
+            parser.feed(piece.as_bytes());
+            assert_eq!(parser.next_value(), Ok(Next::Incomplete), "{}", piece);
+        }
+        parser.feed(b"e }");
+        let error = parser.next_value().unwrap_err();
+        assert_eq!((error.offset, error.expected.as_str()), (33, "',' or ']'"));
+        assert_eq!(parser.next_value(), Err(error));
+    }
+
+    #[test]
+    fn positions_count_from_the_start_of_the_stream() {
+        let mut parser = JsonStreamParser::new();
+        parser.feed(b"[1]\n [2] ");
+        assert_eq!(values(&mut parser).unwrap().len(), 2);
+        parser.feed(b"[3,\n  ,]");
+        let error = parser.next_value().unwrap_err();
+        assert_eq!((error.offset, error.line, error.column), (15, 3, 3));
+        assert_eq!(error.expected, "a value");
+
+        let mut truncated = JsonStreamParser::new();
+        truncated.feed(b"{\"a\": 1");
+        truncated.finish();
+        let error = truncated.next_value().unwrap_err();
+        assert_eq!((error.offset, error.expected.as_str()), (7, "',' or '}'"));
+    }
+
+    #[test]
+    fn utf8() {
+        let bytes = "\"é\"".as_bytes();
+        let mut split = JsonStreamParser::new();
+        split.feed(&bytes[..2]);
+        assert_eq!(split.next_value(), Ok(Next::Incomplete));
+        split.feed(&bytes[2..]);
+        assert_eq!(split.next_value(), Ok(Next::Value(Json::JSString("é".to_string()))));
+
+        let mut invalid = JsonStreamParser::new();
+        invalid.feed(b"[1] [\"\xff\"]");
+        assert_eq!(invalid.next_value(), Ok(Next::Value(Json::JSArray(vec![Json::JSNumber(1.0)]))));
+        let error = invalid.next_value().unwrap_err();
+        assert_eq!((error.offset, error.expected.as_str()), (6, "valid UTF-8"));
+
+        let mut truncated = JsonStreamParser::new();
+        truncated.feed(&bytes[..2]);
+        truncated.finish();
+        assert_eq!(truncated.next_value().unwrap_err().offset, 1);
+    }
+
+    #[test]
+    fn duplicate_keys() {
+        let mut parser = JsonStreamParser::new().duplicate_keys(DuplicateKeys::Error);
+        parser.feed(br#"{"a": 1} {"a": 1, "a": 2}"#);
+        assert!(matches!(parser.next_value(), Ok(Next::Value(_))));
+        let error = parser.next_value().unwrap_err();
+        assert_eq!((error.offset, error.expected.as_str()), (18, "a unique key"));
+    }
+
+    #[test]
+    fn nesting_limit() {
+        let mut parser = JsonStreamParser::new().max_depth(2);
+        parser.feed(b"[[1]] [[[");
+        assert!(matches!(parser.next_value(), Ok(Next::Value(_))));
+        let error = parser.next_value().unwrap_err();
+        assert_eq!((error.offset, error.expected.as_str()), (8, "less nesting"));
+    }
+
+    #[test]
+    fn matches_whole_documents() {
+        let documents = [
+            "", "  ", "true", " -12.5e-1 ", "\"a\\\"b\\u00e9\\ud83d\\ude00\"", "[1, {\"a\": null}, [], {}]",
+            "{\"a\": 1, \"b\": [true, false], \"a\": \"x\"}", "[1,]", "[1,", "[1 2]", "{1}", "{\"a\" 1}",
+            "{\"a\": 1,}", "{\"a\": 1 \"b\": 2}", "{\n  \"a\": tru\n}", "[\"é\", x]", "\"abc", "\"\\q\"",
+            "\"\\u12x4\"", "\"\\ud83d\"", "[1.]", "1e400", "[\"a\nb\"]", "{\"a\": {\"b\": [1, 2", "01",
+        ];
+        for document in &documents {
+            let whole = parse_json(document);
+            for split in 0..=document.len() {
+                let mut parser = JsonStreamParser::new();
+                parser.feed(&document.as_bytes()[..split]);
+                let early = parser.next_value();
+                parser.feed(&document.as_bytes()[split..]);
+                parser.finish();
+                let next = match early {
+                    Ok(Next::Incomplete) => parser.next_value(),
+                    early => early,
+                };
+                match (&whole, next) {
+                    (Ok(json), Ok(Next::Value(value))) => assert_eq!(&value, json, "{:?}", document),
+                    (Err(error), Ok(Next::Value(_))) => assert_eq!(error.expected, "end of input", "{:?}", document),
+                    (Err(error), Ok(Next::Done)) => assert_eq!(error.expected, "a value", "{:?}", document),
+                    (whole, next) => assert_eq!(whole.clone().err(), next.err(), "{:?} split at {}", document, split),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn long_values_are_read_once() {
+        let document = format!("[{}\"{}\"]", "0, ".repeat(100_000), "x".repeat(100_000));
+        let mut parser = JsonStreamParser::new();
+        for byte in document.bytes() {
+            parser.feed(&[byte]);
+            if let Next::Value(Json::JSArray(elements)) = parser.next_value().unwrap() {
+                assert_eq!(elements.len(), 100_001);
+                return;
+            }
+        }
+        panic!("the array was never completed");
+    }
+
+    #[test]
+    fn waits_for_new_input() {
+        let mut parser = JsonStreamParser::new();
+        parser.feed(b"[1, ");
+        assert_eq!(parser.next_value(), Ok(Next::Incomplete));
+        parser.feed(b"");
+        assert_eq!(parser.next_value(), Ok(Next::Incomplete));
+        parser.feed(b"2] 3");
+        assert_eq!(parser.next_value(), Ok(Next::Value(Json::JSArray(vec![Json::JSNumber(1.0), Json::JSNumber(2.0)]))));
+        assert_eq!(parser.next_value(), Ok(Next::Incomplete));
+        parser.finish();
+        assert_eq!(parser.next_value(), Ok(Next::Value(Json::JSNumber(3.0))));
+        assert_eq!(parser.next_value(), Ok(Next::Done));
+        assert_eq!(parser.next_value(), Ok(Next::Done));
+    }
+}
@@ -0,0 +1,75 @@
+//! Times the struct-of-arrays kernels against a plain `Vec` of multivectors.
+//! Run with `cargo run --release --example soa_bench`.
+
+// The crate is a binary, so the example compiles the library modules in
+// directly. Only the batch kernels are used here.
+#[allow(dead_code, unused_imports, clippy::upper_case_acronyms)]
+#[path = "../src/main.rs"]
+mod matrix_mul;
+
+use std::hint::black_box;
+use std::time::Instant;
+
+use matrix_mul::soa::{RotorSoA, Vec3SoA};
+use matrix_mul::{Rotor, Vec3};
+
+const N: usize = 1_000_000;
+const ITERATIONS: u32 = 10;
+
+fn rotor(i: usize) -> Rotor<f64> {
+    let t = i as f64;
+    let a = Vec3::new(t.sin(), 1.0, t * 0.25);
+    let b = Vec3::new(0.5, t.cos(), -1.0);
+    Rotor::from_angle_plane(t * 0.37, &a.wedge(&b))
+}
+
+fn vector(i: usize) -> Vec3<f64> {
+    let t = i as f64;
+    Vec3::new(t.sin(), t * 0.5, 1.0 - t)
+}
+
+fn time<F: FnMut()>(name: &str, mut f: F) {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    println!("{}: {:?} per iteration", name, start.elapsed() / ITERATIONS);
+}
+
+fn main() {
+    let aos_rotors: Vec<Rotor<f64>> = (0..N).map(rotor).collect();
+    let aos_vectors: Vec<Vec3<f64>> = (0..N).map(vector).collect();
+    let mut soa_rotors: RotorSoA<f64> = (0..N).map(rotor).collect();
+    let mut soa_vectors: Vec3SoA<f64> = (0..N).map(vector).collect();
+
+    time("product    Vec<Rotor<f64>>", || {
+        let out: Vec<Rotor<f64>> = aos_rotors.iter().zip(&aos_rotors).map(|(a, b)| a * b).collect();
+        black_box(out);
+    });
+    time("product    RotorSoA<f64>  ", || {
+        black_box(&soa_rotors * &soa_rotors);
+    });
+
+    // The in-place kernels repeat the same work each time, so their batches
+    // are reused rather than copied inside the timed loop.
+    let mut normalized = aos_rotors.clone();
+    time("normalize  Vec<Rotor<f64>>", || {
+        for r in normalized.iter_mut() {
+            *r = r.clone().normalized();
+        }
+        black_box(&normalized);
+    });
+    time("normalize  RotorSoA<f64>  ", || {
+        soa_rotors.normalize();
+        black_box(&soa_rotors);
+    });
+
+    time("rotate     Vec<Rotor<f64>>", || {
+        let out: Vec<_> = aos_rotors.iter().zip(&aos_vectors).map(|(r, v)| &(r * v) * &r.clone().reverse()).collect();
+        black_box(out);
+    });
+    time("rotate     RotorSoA<f64>  ", || {
+        soa_vectors.rotate_each(&soa_rotors);
+        black_box(&soa_vectors);
+    });
+}
@@ -0,0 +1,223 @@
+use super::matrix_generic_stub::{Matrix2, Matrix22};
+use super::multivector::Component;
+use super::real::Ring;
+use super::Just;
+
+/// A sparse matrix in compressed sparse row form: the stored entries of row
+/// `r` are `columns[row_starts[r]..row_starts[r + 1]]`, in increasing column
+/// order, with their values alongside. This is the runtime counterpart of the
+/// `Nil` entries in `Matrix22`, for matrices too big to spell out as types.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CsrMatrix<T> {
+    rows: usize,
+    cols: usize,
+    row_starts: Vec<usize>,
+    columns: Vec<usize>,
+    values: Vec<T>,
+}
+
+impl<T: Ring> CsrMatrix<T> {
+    /// Builds a matrix from `(row, column, value)` triplets in any order.
+    /// Repeated positions are summed. `None` if any position is out of
+    /// bounds.
+    pub fn from_triplets(rows: usize, cols: usize, triplets: &[(usize, usize, T)]) -> Option<Self> {
+        if triplets.iter().any(|&(r, c, _)| r >= rows || c >= cols) {
+            return None;
+        }
+        let mut sorted = triplets.to_vec();
+        sorted.sort_by_key(|&(r, c, _)| (r, c));
+
+        let mut row_starts = vec![0; rows + 1];
+        let mut columns: Vec<usize> = Vec::with_capacity(sorted.len());
+        let mut values: Vec<T> = Vec::with_capacity(sorted.len());
+        let mut last = None;
+        for (r, c, value) in sorted {
+            if last == Some((r, c)) {
+                let sum = values.last_mut().unwrap();
+                *sum = *sum + value;
+                continue;
+            }
+            last = Some((r, c));
+            row_starts[r + 1] += 1;
+            columns.push(c);
+            values.push(value);
+        }
+        for r in 0..rows {
+            row_starts[r + 1] += row_starts[r];
+        }
+        Some(CsrMatrix { rows, cols, row_starts, columns, values })
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+    /// The number of stored entries.
+    pub fn nnz(&self) -> usize {
+        self.values.len()
+    }
+
+    fn row(&self, r: usize) -> impl Iterator<Item = (usize, T)> + '_ {
+        let range = self.row_starts[r]..self.row_starts[r + 1];
+        self.columns[range.clone()].iter().copied().zip(self.values[range].iter().copied())
+    }
+
+    /// The entry at `(r, c)`, which is zero if it is not stored.
+    pub fn get(&self, r: usize, c: usize) -> T {
+        self.row(r).find(|&(column, _)| column == c).map_or_else(T::zero, |(_, value)| value)
+    }
+
+    /// `self * x` for a dense vector `x`. `None` if `x` has the wrong length.
+    pub fn mul_vec(&self, x: &[T]) -> Option<Vec<T>> {
+        if x.len() != self.cols {
+            return None;
+        }
+        let product = (0..self.rows).map(|r| self.row(r).fold(T::zero(), |sum, (c, value)| sum + value * x[c]));
+        Some(product.collect())
+    }
+
+    /// `self * rhs`, by Gustavson's row-by-row method. Only positions where
+    /// both operands have stored entries are stored in the result. `None` if
+    /// the inner dimensions differ.
+    pub fn mul_sparse(&self, rhs: &CsrMatrix<T>) -> Option<CsrMatrix<T>> {
+        if self.cols != rhs.rows {
+            return None;
+        }
+        let mut row_starts = Vec::with_capacity(self.rows + 1);
+        let mut columns = Vec::new();
+        let mut values = Vec::new();
+        // The position in `columns` of each column already seen in the
+        // current row.
+        let mut seen: Vec<Option<usize>> = vec![None; rhs.cols];
+        row_starts.push(0);
+        for r in 0..self.rows {
+            let start = columns.len();
+            for (k, a) in self.row(r) {
+                for (c, b) in rhs.row(k) {
+                    match seen[c] {
+                        Some(i) => values[i] = values[i] + a * b,
+                        None => {
+                            seen[c] = Some(columns.len());
+                            columns.push(c);
+                            values.push(a * b);
+                        }
+                    }
+                }
+            }
+            let mut row: Vec<_> = columns[start..].iter().copied().zip(values[start..].iter().copied()).collect();
+            row.sort_by_key(|&(c, _)| c);
+            for (i, (c, value)) in row.into_iter().enumerate() {
+                seen[c] = None;
+                columns[start + i] = c;
+                values[start + i] = value;
+            }
+            row_starts.push(columns.len());
+        }
+        Some(CsrMatrix { rows: self.rows, cols: rhs.cols, row_starts, columns, values })
+    }
+
+    pub fn transpose(&self) -> CsrMatrix<T> {
+        let mut row_starts = vec![0; self.cols + 1];
+        for &c in &self.columns {
+            row_starts[c + 1] += 1;
+        }
+        for c in 0..self.cols {
+            row_starts[c + 1] += row_starts[c];
+        }
+        let mut next = row_starts.clone();
+        let mut columns = vec![0; self.nnz()];
+        let mut values = vec![T::zero(); self.nnz()];
+        for r in 0..self.rows {
+            for (c, value) in self.row(r) {
+                columns[next[c]] = r;
+                values[next[c]] = value;
+                next[c] += 1;
+            }
+        }
+        CsrMatrix { rows: self.cols, cols: self.rows, row_starts, columns, values }
+    }
+
+    /// The dense 2x2 matrix with the same entries. `None` unless `self` is
+    /// 2x2.
+    pub fn to_matrix22(&self) -> Option<Matrix2<T>> {
+        if (self.rows, self.cols) != (2, 2) {
+            return None;
+        }
+        Some(Matrix22 {
+            ul: Just(self.get(0, 0)), ur: Just(self.get(0, 1)),
+            ll: Just(self.get(1, 0)), lr: Just(self.get(1, 1)),
+        })
+    }
+}
+
+/// Stores exactly the `Just` entries, so `Nil` entries stay absent.
+impl<T: Ring, UL, UR, LL, LR> From<&Matrix22<UL, UR, LL, LR>> for CsrMatrix<T>
+where
+    UL: Component<T>, UR: Component<T>, LL: Component<T>, LR: Component<T>,
+{
+    fn from(m: &Matrix22<UL, UR, LL, LR>) -> Self {
+        let entries = [
+            (0, 0, m.ul.coefficient()), (0, 1, m.ur.coefficient()),
+            (1, 0, m.ll.coefficient()), (1, 1, m.lr.coefficient()),
+        ];
+        let triplets: Vec<_> = entries.iter().filter_map(|&(r, c, value)| Some((r, c, value?))).collect();
+        CsrMatrix::from_triplets(2, 2, &triplets).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Nil;
+
+    fn dense(m: &CsrMatrix<i64>) -> Vec<Vec<i64>> {
+        (0..m.rows()).map(|r| (0..m.cols()).map(|c| m.get(r, c)).collect()).collect()
+    }
+
+    #[test]
+    fn construction() {
+        let m = CsrMatrix::from_triplets(3, 4, &[(2, 1, 5), (0, 3, 1), (2, 1, 2), (0, 0, 4)]).unwrap();
+        assert_eq!(m.nnz(), 3);
+        assert_eq!(dense(&m), vec![vec![4, 0, 0, 1], vec![0, 0, 0, 0], vec![0, 7, 0, 0]]);
+        assert_eq!(CsrMatrix::from_triplets(3, 4, &[(3, 0, 1)]), None);
+        assert_eq!(CsrMatrix::<i64>::from_triplets(0, 0, &[]).unwrap().nnz(), 0);
+    }
+
+    #[test]
+    fn products_and_transpose() {
+        let a = CsrMatrix::from_triplets(2, 3, &[(0, 0, 1), (0, 2, 2), (1, 1, 3)]).unwrap();
+        let b = CsrMatrix::from_triplets(3, 2, &[(0, 1, 4), (1, 0, 5), (2, 0, 6), (2, 1, 7)]).unwrap();
+        assert_eq!(a.mul_vec(&[1, 2, 3]), Some(vec![7, 6]));
+        assert_eq!(a.mul_vec(&[1, 2]), None);
+
+        let ab = a.mul_sparse(&b).unwrap();
+        assert_eq!(dense(&ab), vec![vec![12, 18], vec![15, 0]]);
+        assert_eq!(ab.nnz(), 3);
+        assert_eq!(a.mul_sparse(&a), None);
+
+        assert_eq!(dense(&a.transpose()), vec![vec![1, 0], vec![0, 3], vec![2, 0]]);
+        assert_eq!(a.transpose().transpose(), a);
+        // (AB)^T = B^T A^T
+        assert_eq!(ab.transpose(), b.transpose().mul_sparse(&a.transpose()).unwrap());
+    }
+
+    #[test]
+    fn matrix22_round_trip() {
+        let upper = Matrix22 {
+            ul: Just(1), ur: Just(2),
+            ll: Nil(), lr: Just(3),
+        };
+        let sparse = CsrMatrix::from(&upper);
+        assert_eq!(sparse.nnz(), 3);
+        let square = sparse.mul_sparse(&sparse).unwrap();
+        let dense_square: Matrix2<i64> = Matrix22 {
+            ul: Just(1), ur: Just(8),
+            ll: Just(0), lr: Just(9),
+        };
+        assert_eq!(square.to_matrix22(), Some(dense_square.clone()));
+        assert_eq!(CsrMatrix::from(&dense_square).to_matrix22(), Some(dense_square));
+        assert_eq!(CsrMatrix::from_triplets(3, 2, &[(0, 0, 1)]).unwrap().to_matrix22(), None);
+    }
+}
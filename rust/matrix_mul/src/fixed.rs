@@ -0,0 +1,338 @@
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use super::real::{Real, Ring};
+
+/// What a `Fixed` does with a result that does not fit in 64 bits.
+pub trait Overflow: Copy + Default + Ord + std::hash::Hash + fmt::Debug {
+    fn narrow(value: i128) -> i64;
+}
+
+/// Keep the low 64 bits, like the `wrapping_*` integer operations.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Wrapping;
+impl Overflow for Wrapping {
+    fn narrow(value: i128) -> i64 {
+        value as i64
+    }
+}
+
+/// Clamp to the largest or smallest representable value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Saturating;
+impl Overflow for Saturating {
+    fn narrow(value: i128) -> i64 {
+        value.clamp(i64::MIN as i128, i64::MAX as i128) as i64
+    }
+}
+
+/// A signed Q-format number with `FRAC` fractional bits, stored in an `i64`.
+/// Every operation, including the square root and the transcendental
+/// functions, is done in integer arithmetic, so results are bit-identical on
+/// every machine. `FRAC` may be at most 61; a larger one fails to compile.
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fixed<const FRAC: u32, M = Wrapping> {
+    raw: i64,
+    mode: PhantomData<M>,
+}
+
+// π and ln 2 with 61 fractional bits.
+const PI_Q61: i64 = 7_244_019_458_077_122_842;
+const LN2_Q61: i64 = 1_598_288_580_650_331_957;
+
+impl<const FRAC: u32, M: Overflow> Fixed<FRAC, M> {
+    pub fn from_raw(raw: i64) -> Self {
+        // π and ln 2 are only stored to 61 bits.
+        const { assert!(FRAC <= 61, "Fixed supports at most 61 fractional bits") };
+        Fixed { raw, mode: PhantomData }
+    }
+    pub fn raw(self) -> i64 {
+        self.raw
+    }
+    pub fn from_int(value: i64) -> Self {
+        Fixed::from_raw(M::narrow(i128::from(value) << FRAC))
+    }
+    /// Rounds to the nearest representable value. Conversions from `f64` are
+    /// exact IEEE operations, so they are as deterministic as the rest. Values
+    /// out of range overflow like any other result, except that anything
+    /// beyond the range of an `i128` (and NaN, which gives zero) is clamped
+    /// first.
+    pub fn from_f64(value: f64) -> Self {
+        Fixed::from_raw(M::narrow((value * (FRAC as f64).exp2()).round() as i128))
+    }
+    pub fn to_f64(self) -> f64 {
+        self.raw as f64 / (FRAC as f64).exp2()
+    }
+
+    // Divides by a small integer without first making it a `Fixed`, which
+    // would not fit once `FRAC` is close to 61.
+    fn div_int(self, n: i64) -> Self {
+        Fixed::from_raw(M::narrow(i128::from(self.raw) / i128::from(n)))
+    }
+
+    fn from_q61(value: i64) -> Self {
+        let shift = 61 - FRAC;
+        if shift == 0 {
+            return Fixed::from_raw(value);
+        }
+        Fixed::from_raw((value + (1 << (shift - 1))) >> shift)
+    }
+    pub fn pi() -> Self {
+        Fixed::from_q61(PI_Q61)
+    }
+
+    /// e^x, by writing x = k ln 2 + r with |r| <= ln 2 / 2, summing the Taylor
+    /// series for e^r and shifting by k.
+    pub fn exp(self) -> Self {
+        let ln2 = Fixed::<FRAC, M>::from_q61(LN2_Q61);
+        let k = (i128::from(self.raw) + i128::from(ln2.raw / 2)).div_euclid(i128::from(ln2.raw));
+        let r = Fixed::<FRAC, M>::from_raw(M::narrow(i128::from(self.raw) - k * i128::from(ln2.raw)));
+        let one = Fixed::from_int(1);
+        let mut sum = one;
+        for n in (1..=16).rev() {
+            sum = one + (r * sum).div_int(n);
+        }
+        let raw = i128::from(sum.raw);
+        let scaled = match k {
+            // Every bit of `raw << k` below bit 64 is zero, so this narrows
+            // the same way in either mode.
+            k if k >= 64 => raw.signum() << 64,
+            k if k >= 0 => raw << k,
+            k if k > -127 => raw >> -k,
+            _ => 0,
+        };
+        Fixed::from_raw(M::narrow(scaled))
+    }
+}
+
+impl<const FRAC: u32, M: Overflow> Add for Fixed<FRAC, M> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        Fixed::from_raw(M::narrow(i128::from(self.raw) + i128::from(rhs.raw)))
+    }
+}
+impl<const FRAC: u32, M: Overflow> Sub for Fixed<FRAC, M> {
+    type Output = Self;
+    fn sub(self, rhs: Self) -> Self {
+        Fixed::from_raw(M::narrow(i128::from(self.raw) - i128::from(rhs.raw)))
+    }
+}
+impl<const FRAC: u32, M: Overflow> Mul for Fixed<FRAC, M> {
+    type Output = Self;
+    /// Rounds to nearest, with ties towards positive infinity.
+    fn mul(self, rhs: Self) -> Self {
+        let product = i128::from(self.raw) * i128::from(rhs.raw);
+        let rounding = if FRAC == 0 { 0 } else { 1 << (FRAC - 1) };
+        Fixed::from_raw(M::narrow((product + rounding) >> FRAC))
+    }
+}
+impl<const FRAC: u32, M: Overflow> Div for Fixed<FRAC, M> {
+    type Output = Self;
+    /// Truncates towards zero. Panics on division by zero, like the integer
+    /// types do.
+    fn div(self, rhs: Self) -> Self {
+        Fixed::from_raw(M::narrow((i128::from(self.raw) << FRAC) / i128::from(rhs.raw)))
+    }
+}
+impl<const FRAC: u32, M: Overflow> Neg for Fixed<FRAC, M> {
+    type Output = Self;
+    fn neg(self) -> Self {
+        Fixed::from_raw(M::narrow(-i128::from(self.raw)))
+    }
+}
+
+impl<const FRAC: u32, M> fmt::Debug for Fixed<FRAC, M> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Fixed({})", self.raw as f64 / (FRAC as f64).exp2())
+    }
+}
+
+impl<const FRAC: u32, M: Overflow> Ring for Fixed<FRAC, M> {
+    fn zero() -> Self {
+        Fixed::from_raw(0)
+    }
+    fn one() -> Self {
+        Fixed::from_int(1)
+    }
+}
+
+impl<const FRAC: u32, M: Overflow> Real for Fixed<FRAC, M> {
+    fn from_f64(value: f64) -> Self {
+        Fixed::from_f64(value)
+    }
+    fn epsilon() -> Self {
+        Fixed::from_raw(1)
+    }
+    /// Negative inputs give zero.
+    fn sqrt(self) -> Self {
+        if self.raw <= 0 {
+            return Fixed::zero();
+        }
+        let root = ((self.raw as u128) << FRAC).isqrt();
+        Fixed::from_raw(M::narrow(root as i128))
+    }
+    /// Reduces to [-π/2, π/2] and sums the Taylor series up to x^17.
+    fn sin(self) -> Self {
+        let pi = Fixed::<FRAC, M>::pi();
+        let half_pi = Fixed::<FRAC, M>::from_raw(pi.raw / 2);
+        let two_pi = i128::from(pi.raw) * 2;
+        let turns = (i128::from(self.raw) + two_pi / 2).div_euclid(two_pi);
+        let mut x = Fixed::<FRAC, M>::from_raw((i128::from(self.raw) - turns * two_pi) as i64);
+        if x > half_pi {
+            x = pi - x;
+        } else if x < -half_pi {
+            x = -pi - x;
+        }
+        let one = Fixed::one();
+        let x2 = x * x;
+        let mut sum = one;
+        for k in (1..=10).rev() {
+            sum = one - (x2 * sum).div_int(2 * k * (2 * k + 1));
+        }
+        x * sum
+    }
+    fn cos(self) -> Self {
+        let half_pi = Fixed::<FRAC, M>::from_raw(Fixed::<FRAC, M>::pi().raw / 2);
+        (self + half_pi).sin()
+    }
+    fn sinh(self) -> Self {
+        let two = Fixed::from_int(2);
+        (self.exp() - (-self).exp()) / two
+    }
+    fn cosh(self) -> Self {
+        let two = Fixed::from_int(2);
+        (self.exp() + (-self).exp()) / two
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::csr::CsrMatrix;
+    use crate::matrix_generic_stub::{Matrix2, Matrix22};
+    use crate::soa::RotorSoA;
+    use crate::{Bivec, Just, Multivector3, Nil, Rotor, Se, Vec3};
+
+    type Q32 = Fixed<32>;
+    type Q16 = Fixed<16, Saturating>;
+
+    #[test]
+    fn arithmetic() {
+        assert_eq!(Q32::from_int(3) * Q32::from_f64(0.5), Q32::from_f64(1.5));
+        assert_eq!(Q32::from_int(1) / Q32::from_int(4), Q32::from_f64(0.25));
+        assert_eq!(-Q32::from_f64(2.75) + Q32::from_int(1), Q32::from_f64(-1.75));
+        assert_eq!(Q32::from_f64(-0.5).to_f64(), -0.5);
+        assert!(Q32::from_int(-1) < Q32::from_f64(0.25));
+    }
+
+    #[test]
+    fn overflow_modes() {
+        let big = Q16::from_int(1 << 40);
+        assert_eq!((big * big).raw(), i64::MAX);
+        assert_eq!((-big * big).raw(), i64::MIN);
+        let big = Fixed::<16, Wrapping>::from_int(1 << 46);
+        assert_eq!((big + big).raw(), i64::MIN);
+
+        assert_eq!(Q16::from_f64(1e30).raw(), i64::MAX);
+        assert_eq!(Q16::from_f64(-1e300).raw(), i64::MIN);
+        assert_eq!(Fixed::<16, Wrapping>::from_f64(47f64.exp2()).raw(), i64::MIN);
+        assert_eq!(Q16::from_int(50).exp().raw(), i64::MAX);
+        assert_eq!(Fixed::<16, Wrapping>::from_int(50).exp().raw(), 0);
+    }
+
+    #[test]
+    fn functions_match_f64() {
+        for i in -200..200 {
+            let x = f64::from(i) * 0.05;
+            let q = Q32::from_f64(x);
+            assert!((q.sin().to_f64() - x.sin()).abs() < 1e-8, "sin {}", x);
+            assert!((q.cos().to_f64() - x.cos()).abs() < 1e-8, "cos {}", x);
+            let scale = x.exp().max(1.0);
+            assert!((q.exp().to_f64() - x.exp()).abs() < 1e-8 * scale, "exp {}", x);
+            if x >= 0.0 {
+                assert!((q.sqrt().to_f64() - x.sqrt()).abs() < 1e-8, "sqrt {}", x);
+            }
+        }
+        assert_eq!(Q32::from_int(-4).sqrt(), Q32::zero());
+    }
+
+    #[test]
+    fn functions_at_the_largest_frac() {
+        fn check<M: Overflow>() {
+            for i in -24..=24 {
+                let x = f64::from(i) * 0.05;
+                let q = Fixed::<61, M>::from_f64(x);
+                assert!((q.sin().to_f64() - x.sin()).abs() < 1e-15, "sin {}", x);
+                assert!((q.cos().to_f64() - x.cos()).abs() < 1e-15, "cos {}", x);
+                assert!((q.exp().to_f64() - x.exp()).abs() < 1e-15 * x.exp(), "exp {}", x);
+            }
+        }
+        check::<Wrapping>();
+        check::<Saturating>();
+        let x = Fixed::<58, Wrapping>::from_f64(0.5);
+        assert!((x.sin().to_f64() - 0.5f64.sin()).abs() < 1e-15);
+    }
+
+    #[test]
+    fn rotors() {
+        let plane: Bivec<Q32> = Multivector3 {
+            e: Nil(),
+            e1: Nil(), e2: Nil(), e3: Nil(),
+            e12: Just(Q32::one()), e31: Just(Q32::zero()), e23: Just(Q32::zero()),
+            e123: Nil(),
+        };
+        let x: Vec3<Q32> = Multivector3 {
+            e: Nil(),
+            e1: Just(Q32::one()), e2: Just(Q32::zero()), e3: Just(Q32::zero()),
+            e12: Nil(), e31: Nil(), e23: Nil(),
+            e123: Nil(),
+        };
+        let rotor = Rotor::from_angle_plane(Q32::pi() / Q32::from_int(2), &plane);
+        let y = &(&rotor * &x) * &rotor.clone().reverse();
+        assert!((y.e1.0.to_f64()).abs() < 1e-8);
+        assert!((y.e2.0.to_f64() - 1.0).abs() < 1e-8);
+
+        let scaled = Se(Just(Q32::from_int(3))) * &rotor;
+        let unit = scaled.clone().normalized();
+        assert!(((&unit * &unit.clone().reverse()).e.0.to_f64() - 1.0).abs() < 1e-8);
+
+        let mut batch: RotorSoA<Q32> = vec![scaled.clone(), scaled].into_iter().collect();
+        batch.normalize();
+        assert_eq!(batch.get(0), batch.get(1));
+        assert_eq!(batch.get(0), unit);
+    }
+
+    #[test]
+    fn matrices() {
+        let half = Q32::from_f64(0.5);
+        let m: Matrix2<Q32> = Matrix22 {
+            ul: Just(half), ur: Just(half),
+            ll: Just(Q32::zero()), lr: Just(Q32::one()),
+        };
+        let cubed = m.pow(3);
+        assert_eq!(cubed.ul, Just(Q32::from_f64(0.125)));
+        assert_eq!(cubed.ur, Just(Q32::from_f64(0.875)));
+        assert_eq!(m.trace(), Just(Q32::from_f64(1.5)));
+
+        let sparse = CsrMatrix::from(&m);
+        assert_eq!(sparse.get(1, 1), Q32::one());
+        assert_eq!(sparse.mul_vec(&[Q32::from_int(2), Q32::from_int(4)]), Some(vec![Q32::from_int(3), Q32::from_int(4)]));
+    }
+
+    #[test]
+    fn symmetric_eigen() {
+        let m: Matrix2<Q32> = Matrix22 {
+            ul: Just(Q32::from_int(2)), ur: Just(Q32::one()),
+            ll: Just(Q32::one()), lr: Just(Q32::from_int(2)),
+        };
+        let eigen = m.symmetric_eigen(Q32::zero()).unwrap();
+        for (value, vector) in [1.0, 3.0].iter().zip(&eigen.vectors) {
+            let (x, y) = (vector.x.0.to_f64(), vector.y.0.to_f64());
+            assert!((x * x + y * y - 1.0).abs() < 1e-8);
+            assert!((2.0 * x + y - value * x).abs() < 1e-8);
+            assert!((x + 2.0 * y - value * y).abs() < 1e-8);
+        }
+        assert!((eigen.values[1].to_f64() - 3.0).abs() < 1e-8);
+    }
+}
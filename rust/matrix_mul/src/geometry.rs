@@ -0,0 +1,245 @@
+use super::real::Real;
+use super::{Bivec, Just, Multivector3, Nil, Plane, Vec3, XYZ};
+
+impl<T: Real> Vec3<T> {
+    /// The inner product, which is the scalar part of the geometric product.
+    pub fn dot(&self, other: &Vec3<T>) -> T {
+        (self * other).e.0
+    }
+
+    /// The outer product `a ^ b`, the bivector part of the geometric product.
+    /// It is the oriented parallelogram spanned by the two vectors.
+    pub fn wedge(&self, other: &Vec3<T>) -> Bivec<T> {
+        let product = self * other;
+        Multivector3 {
+            e: Nil(),
+            e1: Nil(), e2: Nil(), e3: Nil(),
+            e12: product.e12, e31: product.e31, e23: product.e23,
+            e123: Nil(),
+        }
+    }
+}
+
+impl<T: Real> Bivec<T> {
+    /// The outer product `B ^ v`, the trivector part of the geometric product.
+    pub fn wedge(&self, other: &Vec3<T>) -> XYZ<T> {
+        Multivector3 {
+            e: Nil(),
+            e1: Nil(), e2: Nil(), e3: Nil(),
+            e12: Nil(), e31: Nil(), e23: Nil(),
+            e123: (self * other).e123,
+        }
+    }
+}
+
+fn sub<T: Real>(a: &Vec3<T>, b: &Vec3<T>) -> Vec3<T> {
+    Vec3::new(a.e1.0 - b.e1.0, a.e2.0 - b.e2.0, a.e3.0 - b.e3.0)
+}
+
+/// `<a ~b>`, which for bivectors is the usual dot product of their
+/// coefficients.
+fn scalar_product<T: Real>(a: &Bivec<T>, b: &Bivec<T>) -> T {
+    (a * &b.clone().reverse()).e.0
+}
+
+/// `a / b` for parallel bivectors, or `None` if `b` is zero. If `a` is not
+/// parallel to `b` this is the ratio of its projection onto `b`.
+fn ratio<T: Real>(a: &Bivec<T>, b: &Bivec<T>) -> Option<T> {
+    let norm = scalar_product(b, b);
+    if norm == T::zero() {
+        return None;
+    }
+    Some(scalar_product(a, b) / norm)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ray<T> {
+    pub origin: Vec3<T>,
+    pub direction: Vec3<T>,
+}
+impl<T: Real> Ray<T> {
+    pub fn at(&self, t: T) -> Vec3<T> {
+        Vec3::new(
+            self.origin.e1.0 + t * self.direction.e1.0,
+            self.origin.e2.0 + t * self.direction.e2.0,
+            self.origin.e3.0 + t * self.direction.e3.0,
+        )
+    }
+}
+
+/// Where a ray crossed a triangle: the ray parameter, and the barycentric
+/// weights of the three corners in the order they were given.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TriangleHit<T> {
+    pub t: T,
+    pub barycentric: (T, T, T),
+}
+
+/// The plane through three points, as the set of `x` with `x ^ B = d I`. The
+/// bivector part holds the orientation `B = (b - a) ^ (c - a)` and the
+/// trivector part holds `d I`. `None` if the points are collinear.
+pub fn plane_through<T: Real>(a: &Vec3<T>, b: &Vec3<T>, c: &Vec3<T>) -> Option<Plane<T>> {
+    let orientation = sub(b, a).wedge(&sub(c, a));
+    if scalar_product(&orientation, &orientation) == T::zero() {
+        return None;
+    }
+    Some(Multivector3 {
+        e: Nil(),
+        e1: Nil(), e2: Nil(), e3: Nil(),
+        e12: orientation.e12, e31: orientation.e31, e23: orientation.e23,
+        e123: orientation.wedge(a).e123,
+    })
+}
+
+/// The parameter `t >= 0` at which the ray meets the plane. Solving
+/// `(o + t d) ^ B = d I` gives `t = (d I - o ^ B) / (d ^ B)`. `None` if the ray
+/// is parallel to the plane or points away from it.
+pub fn ray_plane<T: Real>(ray: &Ray<T>, plane: &Plane<T>) -> Option<T> {
+    let orientation: Bivec<T> = Multivector3 {
+        e: Nil(),
+        e1: Nil(), e2: Nil(), e3: Nil(),
+        e12: plane.e12, e31: plane.e31, e23: plane.e23,
+        e123: Nil(),
+    };
+    let rate = orientation.wedge(&ray.direction).e123.0;
+    if rate == T::zero() {
+        return None;
+    }
+    let t = (plane.e123.0 - orientation.wedge(&ray.origin).e123.0) / rate;
+    if t < T::zero() {
+        return None;
+    }
+    Some(t)
+}
+
+/// The barycentric coordinates of `p` in the triangle `a b c`, as ratios of
+/// the areas `(b - p) ^ (c - p)`, `(c - p) ^ (a - p)` and `(a - p) ^ (b - p)`
+/// to the whole. Points off the triangle's plane are projected onto it first.
+/// `None` if the triangle is degenerate.
+pub fn barycentric<T: Real>(p: &Vec3<T>, a: &Vec3<T>, b: &Vec3<T>, c: &Vec3<T>) -> Option<(T, T, T)> {
+    let whole = sub(b, a).wedge(&sub(c, a));
+    let (pa, pb, pc) = (sub(a, p), sub(b, p), sub(c, p));
+    Some((
+        ratio(&pb.wedge(&pc), &whole)?,
+        ratio(&pc.wedge(&pa), &whole)?,
+        ratio(&pa.wedge(&pb), &whole)?,
+    ))
+}
+
+/// Whether `p`, projected onto the triangle's plane, lies inside the triangle
+/// or on its edges. `None` if the triangle is degenerate.
+pub fn point_in_triangle<T: Real>(p: &Vec3<T>, a: &Vec3<T>, b: &Vec3<T>, c: &Vec3<T>) -> Option<bool> {
+    let (u, v, w) = barycentric(p, a, b, c)?;
+    Some(u >= T::zero() && v >= T::zero() && w >= T::zero())
+}
+
+/// Where the ray crosses the triangle `a b c`, from either side. `None` if it
+/// misses, is parallel to the triangle, or the triangle is degenerate.
+pub fn ray_triangle<T: Real>(ray: &Ray<T>, a: &Vec3<T>, b: &Vec3<T>, c: &Vec3<T>) -> Option<TriangleHit<T>> {
+    let plane = plane_through(a, b, c)?;
+    let t = ray_plane(ray, &plane)?;
+    let barycentric = barycentric(&ray.at(t), a, b, c)?;
+    let (u, v, w) = barycentric;
+    if u < T::zero() || v < T::zero() || w < T::zero() {
+        return None;
+    }
+    Some(TriangleHit { t, barycentric })
+}
+
+/// The oriented area of the triangle `a b c`, `(b - a) ^ (c - a) / 2`.
+pub fn triangle_area<T: Real>(a: &Vec3<T>, b: &Vec3<T>, c: &Vec3<T>) -> Bivec<T> {
+    let half = T::one() / (T::one() + T::one());
+    let area = sub(b, a).wedge(&sub(c, a));
+    Multivector3 {
+        e: Nil(),
+        e1: Nil(), e2: Nil(), e3: Nil(),
+        e12: Just(half * area.e12.0), e31: Just(half * area.e31.0), e23: Just(half * area.e23.0),
+        e123: Nil(),
+    }
+}
+
+/// The area of the triangle `a b c` projected onto `plane`, positive if the
+/// corners run the same way round as `plane`. `None` if `plane` is zero.
+pub fn signed_triangle_area<T: Real>(a: &Vec3<T>, b: &Vec3<T>, c: &Vec3<T>, plane: &Bivec<T>) -> Option<T> {
+    let norm = scalar_product(plane, plane);
+    if norm == T::zero() {
+        return None;
+    }
+    Some(scalar_product(&triangle_area(a, b, c), plane) / norm.sqrt())
+}
+
+/// The signed volume of the tetrahedron `a b c d`,
+/// `(b - a) ^ (c - a) ^ (d - a) / 6`, positive if `b - a`, `c - a` and `d - a`
+/// are right-handed.
+pub fn tetrahedron_volume<T: Real>(a: &Vec3<T>, b: &Vec3<T>, c: &Vec3<T>, d: &Vec3<T>) -> T {
+    let six = T::from_f64(6.0);
+    sub(b, a).wedge(&sub(c, a)).wedge(&sub(d, a)).e123.0 / six
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-12
+    }
+
+    #[test]
+    fn wedge_products() {
+        let (x, y, z) = (Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        let xy = x.wedge(&y);
+        assert_eq!((xy.e12.0, xy.e31.0, xy.e23.0), (1.0, 0.0, 0.0));
+        assert_eq!(y.wedge(&x).e12.0, -1.0);
+        assert_eq!(x.wedge(&x), triangle_area(&x, &x, &x));
+        assert_eq!(xy.wedge(&z).e123.0, 1.0);
+        assert_eq!(x.dot(&Vec3::new(2.0, 3.0, 4.0)), 2.0);
+    }
+
+    #[test]
+    fn rays() {
+        let (a, b, c) = (Vec3::new(0.0, 0.0, 1.0), Vec3::new(2.0, 0.0, 1.0), Vec3::new(0.0, 2.0, 1.0));
+        let ray = Ray { origin: Vec3::new(0.5, 0.5, -1.0), direction: Vec3::new(0.0, 0.0, 2.0) };
+        let plane = plane_through(&a, &b, &c).unwrap();
+        assert_eq!(ray_plane(&ray, &plane), Some(1.0));
+
+        let hit = ray_triangle(&ray, &a, &b, &c).unwrap();
+        assert_eq!(hit.t, 1.0);
+        let (u, v_, w) = hit.barycentric;
+        assert!(close(u, 0.5) && close(v_, 0.25) && close(w, 0.25));
+
+        let reversed = Ray { origin: ray.origin.clone(), direction: Vec3::new(0.0, 0.0, -1.0) };
+        assert_eq!(ray_triangle(&reversed, &a, &b, &c), None);
+        let miss = Ray { origin: Vec3::new(1.5, 1.5, -1.0), direction: Vec3::new(0.0, 0.0, 1.0) };
+        assert_eq!(ray_triangle(&miss, &a, &b, &c), None);
+        let parallel = Ray { origin: Vec3::new(0.0, 0.0, 0.0), direction: Vec3::new(1.0, 1.0, 0.0) };
+        assert_eq!(ray_plane(&parallel, &plane), None);
+    }
+
+    #[test]
+    fn degenerate_input() {
+        let (a, b) = (Vec3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 1.0, 1.0));
+        let c = Vec3::new(2.0, 2.0, 2.0);
+        let ray = Ray { origin: Vec3::new(0.0, 1.0, 0.0), direction: Vec3::new(1.0, 0.0, 0.0) };
+        assert_eq!(plane_through(&a, &b, &c), None);
+        assert_eq!(ray_triangle(&ray, &a, &b, &c), None);
+        assert_eq!(barycentric(&a, &a, &b, &c), None);
+        assert_eq!(point_in_triangle(&a, &a, &b, &c), None);
+        assert_eq!(signed_triangle_area(&a, &b, &c, &a.wedge(&b)), None);
+        assert_eq!(tetrahedron_volume(&a, &b, &c, &Vec3::new(3.0, 0.0, 1.0)), 0.0);
+    }
+
+    #[test]
+    fn areas_and_volumes() {
+        let (o, x, y, z) = (Vec3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        let e12 = x.wedge(&y);
+        assert_eq!(signed_triangle_area(&o, &x, &y, &e12), Some(0.5));
+        assert_eq!(signed_triangle_area(&o, &y, &x, &e12), Some(-0.5));
+        assert_eq!(signed_triangle_area(&o, &x, &z, &e12), Some(0.0));
+        assert!(close(tetrahedron_volume(&o, &x, &y, &z), 1.0 / 6.0));
+        assert!(close(tetrahedron_volume(&o, &y, &x, &z), -1.0 / 6.0));
+
+        assert_eq!(point_in_triangle(&Vec3::new(0.2, 0.2, 5.0), &o, &x, &y), Some(true));
+        assert_eq!(point_in_triangle(&Vec3::new(0.6, 0.6, 0.0), &o, &x, &y), Some(false));
+        assert_eq!(point_in_triangle(&x, &o, &x, &y), Some(true));
+    }
+}
@@ -0,0 +1,184 @@
+use super::real::Real;
+use super::{Bivec, Just, Multivector3, Nil, Rotor, Vec3};
+
+/// A revolute joint followed by a rigid link. The joint turns in `plane`, a
+/// unit bivector in the frame of the previous link, and the link then reaches
+/// `offset` along the joint's rotated frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Joint<T> {
+    pub plane: Bivec<T>,
+    pub offset: Vec3<T>,
+}
+
+/// The world pose of the far end of a link.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Link<T> {
+    pub position: Vec3<T>,
+    pub orientation: Rotor<T>,
+}
+
+/// The rate of change of the end effector's pose with one joint angle: how
+/// fast its position moves, and the plane it turns in scaled by how fast it
+/// turns, both in the world frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Twist<T> {
+    pub linear: Vec3<T>,
+    pub angular: Bivec<T>,
+}
+
+/// A serial chain of joints hanging off a base at the origin.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KinematicChain<T> {
+    pub joints: Vec<Joint<T>>,
+}
+
+fn identity<T: Real>() -> Rotor<T> {
+    Multivector3 {
+        e: Just(T::one()),
+        e1: Nil(), e2: Nil(), e3: Nil(),
+        e12: Just(T::zero()), e31: Just(T::zero()), e23: Just(T::zero()),
+        e123: Nil(),
+    }
+}
+
+fn rotate<T: Real>(r: &Rotor<T>, v: &Vec3<T>) -> Vec3<T> {
+    let rotated = &(r * v) * &r.clone().reverse();
+    Vec3::new(rotated.e1.0, rotated.e2.0, rotated.e3.0)
+}
+
+impl<T: Real> KinematicChain<T> {
+    pub fn new(joints: Vec<Joint<T>>) -> Self {
+        KinematicChain { joints }
+    }
+
+    /// The pose of every link for the given joint angles, from the base
+    /// outwards. `None` unless there is exactly one angle per joint.
+    pub fn forward(&self, angles: &[T]) -> Option<Vec<Link<T>>> {
+        if angles.len() != self.joints.len() {
+            return None;
+        }
+        let mut orientation = identity();
+        let mut position = Vec3::new(T::zero(), T::zero(), T::zero());
+        let links = self.joints.iter().zip(angles).map(|(joint, &angle)| {
+            orientation = &orientation * &Rotor::from_angle_plane(angle, &joint.plane);
+            position = position.clone() + rotate(&orientation, &joint.offset);
+            Link { position: position.clone(), orientation: orientation.clone() }
+        });
+        Some(links.collect())
+    }
+
+    /// The world position of the end of the last link.
+    pub fn end_effector(&self, angles: &[T]) -> Option<Vec3<T>> {
+        self.end_link(angles).map(|link| link.position)
+    }
+
+    // The pose of the end of the last link, or of the base for an empty chain.
+    fn end_link(&self, angles: &[T]) -> Option<Link<T>> {
+        let links = self.forward(angles)?;
+        Some(links.last().cloned().unwrap_or(Link {
+            position: Vec3::new(T::zero(), T::zero(), T::zero()),
+            orientation: identity(),
+        }))
+    }
+
+    /// The derivative of the end effector pose with respect to each joint
+    /// angle, one column per joint, by central differences with the given
+    /// step. The angular part comes from `dR/dθ ~R`, which is minus half the
+    /// world-frame plane of the joint for a unit rate.
+    pub fn jacobian(&self, angles: &[T], step: T) -> Option<Vec<Twist<T>>> {
+        let here = self.end_link(angles)?;
+        let reverse = here.orientation.reverse();
+        let two_step = step + step;
+        let mut nudged = angles.to_vec();
+        (0..angles.len())
+            .map(|i| {
+                nudged[i] = angles[i] + step;
+                let ahead = self.end_link(&nudged)?;
+                nudged[i] = angles[i] - step;
+                let behind = self.end_link(&nudged)?;
+                nudged[i] = angles[i];
+                let (ahead_turn, behind_turn) = (&ahead.orientation * &reverse, &behind.orientation * &reverse);
+                Some(Twist {
+                    linear: Vec3::new(
+                        (ahead.position.e1.0 - behind.position.e1.0) / two_step,
+                        (ahead.position.e2.0 - behind.position.e2.0) / two_step,
+                        (ahead.position.e3.0 - behind.position.e3.0) / two_step,
+                    ),
+                    angular: Multivector3 {
+                        e: Nil(),
+                        e1: Nil(), e2: Nil(), e3: Nil(),
+                        e12: Just((behind_turn.e12.0 - ahead_turn.e12.0) / step),
+                        e31: Just((behind_turn.e31.0 - ahead_turn.e31.0) / step),
+                        e23: Just((behind_turn.e23.0 - ahead_turn.e23.0) / step),
+                        e123: Nil(),
+                    },
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::f64::consts::FRAC_PI_2;
+
+    fn close(a: &Vec3<f64>, b: &Vec3<f64>) -> bool {
+        (a.e1.0 - b.e1.0).abs() < 1e-8 && (a.e2.0 - b.e2.0).abs() < 1e-8 && (a.e3.0 - b.e3.0).abs() < 1e-8
+    }
+    fn close_plane(a: &Bivec<f64>, b: &Bivec<f64>) -> bool {
+        (a.e12.0 - b.e12.0).abs() < 1e-8 && (a.e31.0 - b.e31.0).abs() < 1e-8 && (a.e23.0 - b.e23.0).abs() < 1e-8
+    }
+    fn e12() -> Bivec<f64> {
+        let (x, y) = (Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0));
+        x.wedge(&y)
+    }
+    fn planar_arm(l1: f64, l2: f64) -> KinematicChain<f64> {
+        KinematicChain::new(vec![
+            Joint { plane: e12(), offset: Vec3::new(l1, 0.0, 0.0) },
+            Joint { plane: e12(), offset: Vec3::new(l2, 0.0, 0.0) },
+        ])
+    }
+
+    #[test]
+    fn planar_arm_poses() {
+        let arm = planar_arm(1.0, 2.0);
+        let links = arm.forward(&[FRAC_PI_2, -FRAC_PI_2]).unwrap();
+        assert!(close(&links[0].position, &Vec3::new(0.0, 1.0, 0.0)));
+        assert!(close(&links[1].position, &Vec3::new(2.0, 1.0, 0.0)));
+        assert!((links[1].orientation.e.0 - 1.0).abs() < 1e-12);
+        assert_eq!(arm.forward(&[0.0]), None);
+        assert_eq!(KinematicChain::<f64>::new(vec![]).end_effector(&[]), Some(Vec3::new(0.0, 0.0, 0.0)));
+    }
+
+    #[test]
+    fn out_of_plane_joint() {
+        // A shoulder turning about z followed by an elbow turning about the
+        // shoulder's rotated y axis.
+        let (x, z) = (Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 0.0, 1.0));
+        let arm = KinematicChain::new(vec![
+            Joint { plane: e12(), offset: Vec3::new(0.0, 0.0, 0.0) },
+            Joint { plane: z.wedge(&x), offset: Vec3::new(1.0, 0.0, 0.0) },
+        ]);
+        let end = arm.end_effector(&[FRAC_PI_2, FRAC_PI_2]).unwrap();
+        assert!(close(&end, &Vec3::new(0.0, 0.0, -1.0)));
+
+        // The shoulder has turned the elbow's plane from z^x to z^y.
+        let columns = arm.jacobian(&[FRAC_PI_2, FRAC_PI_2], 1e-5).unwrap();
+        let y = Vec3::new(0.0, 1.0, 0.0);
+        assert!(close_plane(&columns[0].angular, &e12()));
+        assert!(close_plane(&columns[1].angular, &z.wedge(&y)));
+    }
+
+    #[test]
+    fn jacobian_matches_analytic() {
+        let (l1, l2) = (1.0, 2.0);
+        let (t1, t2) = (0.3, 1.1);
+        let columns = planar_arm(l1, l2).jacobian(&[t1, t2], 1e-5).unwrap();
+        let (s1, c1, s12, c12) = (t1.sin(), t1.cos(), (t1 + t2).sin(), (t1 + t2).cos());
+        assert!(close(&columns[0].linear, &Vec3::new(-l1 * s1 - l2 * s12, l1 * c1 + l2 * c12, 0.0)));
+        assert!(close(&columns[1].linear, &Vec3::new(-l2 * s12, l2 * c12, 0.0)));
+        assert!(close_plane(&columns[0].angular, &e12()) && close_plane(&columns[1].angular, &e12()));
+        assert_eq!(planar_arm(l1, l2).jacobian(&[t1], 1e-5), None);
+    }
+}
@@ -1,9 +1,22 @@
 use std::ops::Neg;
 use std::ops::Add;
 use std::ops::Mul;
-use std::mem::size_of;
 use std::io;
 
+use real::Real;
+
+pub mod csr;
+pub mod fixed;
+pub mod geometry;
+pub mod kinematics;
+pub mod matrix_generic_stub;
+pub mod multivector;
+pub mod physics;
+pub mod random;
+pub mod real;
+pub mod soa;
+pub mod spacetime;
+
 #[derive(Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
 pub struct Multivector3<E, E1, E2, E3, E12, E31, E23, E123> {
     e: E,
@@ -32,6 +45,17 @@ pub type Sphere<T> = Multivector3<Just<T>, Just<T>, Just<T>, Just<T>, Nil, Nil,
 pub type Plane<T> = Multivector3<Nil, Nil, Nil, Nil, Just<T>, Just<T>, Just<T>, Just<T>>;
 pub type Frame<T> = Multivector3<Just<T>, Just<T>, Just<T>, Just<T>, Just<T>, Just<T>, Just<T>, Just<T>>;
 
+impl<T> Vec3<T> {
+    pub fn new(x: T, y: T, z: T) -> Self {
+        Multivector3 {
+            e: Nil(),
+            e1: Just(x), e2: Just(y), e3: Just(z),
+            e12: Nil(), e31: Nil(), e23: Nil(),
+            e123: Nil(),
+        }
+    }
+}
+
 macro_rules! mvec_e {
     () => {
         Multivector3<E, E1, E2, E3, E12, E31, E23, E123>
@@ -43,6 +67,61 @@ pub struct Just<T>(T);
 #[derive(Copy, Clone, Debug, Hash, PartialEq)]
 pub struct Nil();
 
+/// The common type two different coefficient types are converted to before
+/// they are combined, so that e.g. `Vec3<f32> * Vec3<f64>` is computed in
+/// `f64`. Only the pairs listed below are promoted; matching types go
+/// through the plain `Add` and `Mul` impls on `Just<T>`.
+pub trait Promote<Rhs> {
+    type Output;
+    fn promote(self) -> Self::Output;
+    fn promote_rhs(rhs: Rhs) -> Self::Output;
+}
+macro_rules! promote {
+    ($narrow: ty => $wide: ty) => {
+        impl Promote<$wide> for $narrow {
+            type Output = $wide;
+            fn promote(self) -> $wide {
+                <$wide>::from(self)
+            }
+            fn promote_rhs(rhs: $wide) -> $wide {
+                rhs
+            }
+        }
+        impl Promote<$narrow> for $wide {
+            type Output = $wide;
+            fn promote(self) -> $wide {
+                self
+            }
+            fn promote_rhs(rhs: $narrow) -> $wide {
+                <$wide>::from(rhs)
+            }
+        }
+        promote!(@ops $narrow, $wide);
+        promote!(@ops $wide, $narrow);
+    };
+    (@ops $lhs: ty, $rhs: ty) => {
+        impl Add<Just<$rhs>> for Just<$lhs> {
+            type Output = Just<<$lhs as Promote<$rhs>>::Output>;
+            fn add(self, rhs: Just<$rhs>) -> Self::Output {
+                Just(<$lhs as Promote<$rhs>>::promote(self.0) + <$lhs>::promote_rhs(rhs.0))
+            }
+        }
+        impl Mul<Just<$rhs>> for Just<$lhs> {
+            type Output = Just<<$lhs as Promote<$rhs>>::Output>;
+            fn mul(self, rhs: Just<$rhs>) -> Self::Output {
+                Just(<$lhs as Promote<$rhs>>::promote(self.0) * <$lhs>::promote_rhs(rhs.0))
+            }
+        }
+    };
+}
+promote!(f32 => f64);
+promote!(i32 => f64);
+promote!(i32 => i64);
+promote!(i16 => i32);
+promote!(i16 => f32);
+promote!(i16 => f64);
+promote!(i16 => i64);
+
 impl<T: Add> Add for Just<T> {
     type Output = Just<<T as Add>::Output>;
     fn add(self, rhs: Self) -> Self::Output {
@@ -553,6 +632,52 @@ where
     }
 }
 
+impl<E, E1, E2, E3, E12: Neg, E31: Neg, E23: Neg, E123: Neg> mvec_e!() {
+    /// Reverses the order of the basis vectors in every blade, which flips
+    /// the sign of the bivector and trivector parts.
+    #[allow(clippy::type_complexity)]
+    pub fn reverse(self) -> Multivector3<
+        E, E1, E2, E3,
+        <E12 as Neg>::Output,
+        <E31 as Neg>::Output,
+        <E23 as Neg>::Output,
+        <E123 as Neg>::Output,
+    > {
+        Multivector3 {
+            e: self.e,
+            e1: self.e1,
+            e2: self.e2,
+            e3: self.e3,
+            e12: -self.e12,
+            e31: -self.e31,
+            e23: -self.e23,
+            e123: -self.e123,
+        }
+    }
+}
+
+impl<T: Real> Rotor<T> {
+    /// The rotor `exp(-plane angle / 2)`. Applied as `R v ~R` it rotates by
+    /// `angle` in the plane of the unit bivector `plane`, from e1 towards e2
+    /// for `plane = e12`.
+    pub fn from_angle_plane(angle: T, plane: &Bivec<T>) -> Self {
+        let half = angle / (T::one() + T::one());
+        let (c, s) = (half.cos(), -half.sin());
+        Multivector3 {
+            e: Just(c),
+            e1: Nil(), e2: Nil(), e3: Nil(),
+            e12: Just(s * plane.e12.0), e31: Just(s * plane.e31.0), e23: Just(s * plane.e23.0),
+            e123: Nil(),
+        }
+    }
+
+    /// Scales the rotor so that `R ~R = 1`.
+    pub fn normalized(self) -> Self {
+        let norm = (&self * &self.clone().reverse()).e.0.sqrt();
+        Se(Just(T::one() / norm)) * &self
+    }
+}
+
 pub fn main() {
     let mut line_buf = String::new();
     io::stdin().read_line(&mut line_buf).unwrap();
@@ -588,6 +713,7 @@ pub fn main() {
 #[cfg(test)]
 mod test {
     use super:: *;
+    use std::mem::size_of;
 
     #[test]
     fn multiply_in_parts() {
@@ -683,6 +809,38 @@ mod test {
         }
     }
 
+    #[test]
+    fn mixed_precision() {
+        let a: Vec3<f32> = Multivector3 {
+            e: Nil(),
+            e1: Just(0.5), e2: Just(0.25), e3: Just(2.0),
+            e12: Nil(), e31: Nil(), e23: Nil(),
+            e123: Nil(),
+        };
+        let b: Vec3<f64> = Multivector3 {
+            e: Nil(),
+            e1: Just(0.1), e2: Just(3.0), e3: Just(-1.0),
+            e12: Nil(), e31: Nil(), e23: Nil(),
+            e123: Nil(),
+        };
+        let product: Rotor<f64> = &a * &b;
+        assert_eq!(product.e, Just(0.5 * 0.1 + 0.25 * 3.0 - 2.0));
+        let wide: Vec3<f64> = Multivector3 {
+            e: Nil(),
+            e1: Just(0.5), e2: Just(0.25), e3: Just(2.0),
+            e12: Nil(), e31: Nil(), e23: Nil(),
+            e123: Nil(),
+        };
+        assert_eq!(product, &wide * &b);
+
+        let sum: Vec3<f64> = a.clone() + b;
+        assert_eq!(sum.e2, Just(3.25));
+
+        assert_eq!(Just(3i32) * Just(0.5f64), Just(1.5f64));
+        assert_eq!(Just(0.5f64) + Just(3i32), Just(3.5f64));
+        assert_eq!(Just(i32::MAX) * Just(2i64), Just(i64::from(i32::MAX) * 2));
+    }
+
     #[test] 
     fn sizes () {
         assert_eq!(size_of::<W<f64>>(), size_of::<f64>());
@@ -1,20 +1,29 @@
-trait DotProduct {
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::ops::{Add, Mul, Neg, Rem, Sub};
+
+use super::multivector::{Coefficient, Component, GeometricProduct, JoinAll, Multivector};
+use super::real::{Real, Ring};
+use super::{Frame, Just, Multivector3, Nil, Rotor, Vec3};
+
+pub trait DotProduct {
     type Output;
-    fn value(&self) -> Self::Output;
+    fn value(self) -> Self::Output;
 }
-impl<R1: Copy, R2: Copy, C1: Copy, C2: Copy> DotProduct for ((R1, R2), (C1, C2))
+impl<R1, R2, C1, C2> DotProduct for ((R1, R2), (C1, C2))
 where
     R1: Mul<C1>,
     R2: Mul<C2>,
     <R1 as Mul<C1>>::Output: Add<<R2 as Mul<C2>>::Output>,
 {
     type Output = <<R1 as Mul<C1>>::Output as Add<<R2 as Mul<C2>>::Output>>::Output;
-    fn value(&self) -> Self::Output{
-        let ((r1, r2), (c1, c2)) = *self;
+    fn value(self) -> Self::Output {
+        let ((r1, r2), (c1, c2)) = self;
         r1 * c1 + r2 * c2
     }
 }
-impl<R1: Copy, R2: Copy, R3: Copy, C1: Copy, C2: Copy, C3: Copy> DotProduct 
+impl<R1, R2, R3, C1, C2, C3> DotProduct 
 for ((R1, R2, R3), (C1, C2, C3))
 where
     ((R1, R2), (C1, C2)): DotProduct,
@@ -22,18 +31,32 @@ where
     <((R1, R2), (C1, C2)) as DotProduct>::Output: Add<<R3 as Mul<C3>>::Output>
 {
     type Output = <<((R1, R2), (C1, C2)) as DotProduct>::Output as Add<<R3 as Mul<C3>>::Output>>::Output;
-    fn value(&self) -> Self::Output{
-        let ((r1, r2, r3), (c1, c2, c3)) = *self;
+    fn value(self) -> Self::Output {
+        let ((r1, r2, r3), (c1, c2, c3)) = self;
         ((r1, r2), (c1, c2)).value() + r3 * c3
     }
 }
 
-#[derive(Clone, Debug)]
-struct Matrix22<UL, UR, LL, LR> {
-    ul: UL, ur: UR,
-    ll: LL, lr: LR,
+impl<R1, R2, R3, R4, C1, C2, C3, C4> DotProduct 
+for ((R1, R2, R3, R4), (C1, C2, C3, C4))
+where
+    ((R1, R2, R3), (C1, C2, C3)): DotProduct,
+    R4: Mul<C4>,
+    <((R1, R2, R3), (C1, C2, C3)) as DotProduct>::Output: Add<<R4 as Mul<C4>>::Output>
+{
+    type Output = <<((R1, R2, R3), (C1, C2, C3)) as DotProduct>::Output as Add<<R4 as Mul<C4>>::Output>>::Output;
+    fn value(self) -> Self::Output {
+        let ((r1, r2, r3, r4), (c1, c2, c3, c4)) = self;
+        ((r1, r2, r3), (c1, c2, c3)).value() + r4 * c4
+    }
 }
-impl<UL1: Copy, UR1: Copy, LL1: Copy, LR1: Copy, UL2: Copy, UR2: Copy, LL2: Copy, LR2: Copy>
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Matrix22<UL, UR, LL, LR> {
+    pub(crate) ul: UL, pub(crate) ur: UR,
+    pub(crate) ll: LL, pub(crate) lr: LR,
+}
+impl<UL1: Clone, UR1: Clone, LL1: Clone, LR1: Clone, UL2: Clone, UR2: Clone, LL2: Clone, LR2: Clone>
     Mul<&Matrix22<UL2, UR2, LL2, LR2>> for &Matrix22<UL1, UR1, LL1, LR1> 
 where
     ((UL1, UR1), (UL2, LL2)): DotProduct,
@@ -50,14 +73,14 @@ where
 
     fn mul(self, rhs: &Matrix22<UL2, UR2, LL2, LR2>) -> Self::Output {
         Matrix22 {
-            ul: ((self.ul, self.ur), (rhs.ul, rhs.ll)).value(),
-            ur: ((self.ul, self.ur), (rhs.ur, rhs.lr)).value(),
-            ll: ((self.ll, self.lr), (rhs.ul, rhs.ll)).value(),
-            lr: ((self.ll, self.lr), (rhs.ur, rhs.lr)).value(),
+            ul: ((self.ul.clone(), self.ur.clone()), (rhs.ul.clone(), rhs.ll.clone())).value(),
+            ur: ((self.ul.clone(), self.ur.clone()), (rhs.ur.clone(), rhs.lr.clone())).value(),
+            ll: ((self.ll.clone(), self.lr.clone()), (rhs.ul.clone(), rhs.ll.clone())).value(),
+            lr: ((self.ll.clone(), self.lr.clone()), (rhs.ur.clone(), rhs.lr.clone())).value(),
         }
     }
 }
-impl<UL1: Copy, UR1: Copy, LL1: Copy, LR1: Copy, UL2: Copy, UR2: Copy, LL2: Copy, LR2: Copy>
+impl<UL1: Clone, UR1: Clone, LL1: Clone, LR1: Clone, UL2: Clone, UR2: Clone, LL2: Clone, LR2: Clone>
     Add<&Matrix22<UL2, UR2, LL2, LR2>> for &Matrix22<UL1, UR1, LL1, LR1> 
 where
     UL1: Add<UL2>,
@@ -74,39 +97,1590 @@ where
 
     fn add(self, rhs: &Matrix22<UL2, UR2, LL2, LR2>) -> Self::Output {
         Matrix22 {
-            ul: self.ul + rhs.ul,
-            ur: self.ur + rhs.ur,
-            ll: self.ll + rhs.ll,
-            lr: self.lr + rhs.lr,
+            ul: self.ul.clone() + rhs.ul.clone(),
+            ur: self.ur.clone() + rhs.ur.clone(),
+            ll: self.ll.clone() + rhs.ll.clone(),
+            lr: self.lr.clone() + rhs.lr.clone(),
         }
     }
 }
 
-fn main() {
-    let mut line_buf = String::new();
-    io::stdin().read_line(&mut line_buf).unwrap();
-    let mut nums = line_buf.split_whitespace()
-        .map(|str| str.parse::<i32>().unwrap());
+// By-value products and sums, so that a `Matrix22` can itself be the entry of
+// a `Matrix22`, and the `DotProduct`s above multiply whole blocks.
+impl<UL1: Clone, UR1: Clone, LL1: Clone, LR1: Clone, UL2: Clone, UR2: Clone, LL2: Clone, LR2: Clone>
+    Mul<Matrix22<UL2, UR2, LL2, LR2>> for Matrix22<UL1, UR1, LL1, LR1>
+where
+    ((UL1, UR1), (UL2, LL2)): DotProduct,
+    ((UL1, UR1), (UR2, LR2)): DotProduct,
+    ((LL1, LR1), (UL2, LL2)): DotProduct,
+    ((LL1, LR1), (UR2, LR2)): DotProduct,
+{
+    type Output = Matrix22<
+        <((UL1, UR1), (UL2, LL2)) as DotProduct>::Output, 
+        <((UL1, UR1), (UR2, LR2)) as DotProduct>::Output,
+        <((LL1, LR1), (UL2, LL2)) as DotProduct>::Output, 
+        <((LL1, LR1), (UR2, LR2)) as DotProduct>::Output,
+    >;
 
-    let matrix_identity = Matrix22 {
-        ul: Just(1),   ur: Nil(),
-        ll: Nil(),      lr: Just(1),
-    };
-    let matrix_full = Matrix22 {
-        ul: Just(nums.next().unwrap()),   ur: Just(nums.next().unwrap()),
-        ll: Just(nums.next().unwrap()),   lr: Just(nums.next().unwrap()),
+    fn mul(self, rhs: Matrix22<UL2, UR2, LL2, LR2>) -> Self::Output {
+        &self * &rhs
+    }
+}
+impl<UL1: Clone, UR1: Clone, LL1: Clone, LR1: Clone, UL2: Clone, UR2: Clone, LL2: Clone, LR2: Clone>
+    Add<Matrix22<UL2, UR2, LL2, LR2>> for Matrix22<UL1, UR1, LL1, LR1>
+where
+    UL1: Add<UL2>,
+    UR1: Add<UR2>,
+    LL1: Add<LL2>,
+    LR1: Add<LR2>,
+{
+    type Output = Matrix22<
+        <UL1 as Add<UL2>>::Output, 
+        <UR1 as Add<UR2>>::Output,
+        <LL1 as Add<LL2>>::Output,
+        <LR1 as Add<LR2>>::Output
+    >;
+
+    fn add(self, rhs: Matrix22<UL2, UR2, LL2, LR2>) -> Self::Output {
+        &self + &rhs
+    }
+}
+
+// A `Nil` block is a zero matrix of whatever size its neighbours are.
+impl<UL, UR, LL, LR> Mul<Matrix22<UL, UR, LL, LR>> for Nil {
+    type Output = Nil;
+    fn mul(self, _rhs: Matrix22<UL, UR, LL, LR>) -> Nil {
+        Nil()
+    }
+}
+impl<UL, UR, LL, LR> Mul<Nil> for Matrix22<UL, UR, LL, LR> {
+    type Output = Nil;
+    fn mul(self, _rhs: Nil) -> Nil {
+        Nil()
+    }
+}
+impl<UL, UR, LL, LR> Add<Matrix22<UL, UR, LL, LR>> for Nil {
+    type Output = Matrix22<UL, UR, LL, LR>;
+    fn add(self, rhs: Matrix22<UL, UR, LL, LR>) -> Self::Output {
+        rhs
+    }
+}
+impl<UL, UR, LL, LR> Add<Nil> for Matrix22<UL, UR, LL, LR> {
+    type Output = Self;
+    fn add(self, _rhs: Nil) -> Self {
+        self
+    }
+}
+
+/// A dense square matrix of side `2n`, built from four blocks of side `n`.
+/// Nest it to get any power of two, as in `Block8<T>`.
+pub type Matrix2n<M> = Matrix22<M, M, M, M>;
+pub type Matrix2<T> = Matrix2n<Just<T>>;
+/// A 4x4 matrix as a 2x2 matrix of 2x2 blocks. The dense one is `Matrix4`.
+pub type Block4<T> = Matrix2n<Matrix2<T>>;
+pub type Block8<T> = Matrix2n<Block4<T>>;
+pub type Block16<T> = Matrix2n<Block8<T>>;
+
+impl<T: Ring> Matrix2<T> {
+    pub fn identity() -> Self {
+        Matrix22 {
+            ul: Just(T::one()), ur: Just(T::zero()),
+            ll: Just(T::zero()), lr: Just(T::one()),
+        }
+    }
+
+    /// `self` to the `n`th power, by repeated squaring in `O(log n)`
+    /// multiplications.
+    pub fn pow(&self, n: u64) -> Self {
+        self.pow_by(n, |a, b| a * b)
+    }
+
+    /// `self` to the `n`th power with every entry reduced into `[0, m)`.
+    /// Each product of two entries is reduced before it is added, so
+    /// `(m - 1) * (m - 1)` and `2 * m` must fit in `T`.
+    ///
+    /// # Panics
+    ///
+    /// If `m` is zero.
+    pub fn pow_mod(&self, n: u64, m: T) -> Self
+    where
+        T: Rem<Output = T> + PartialEq,
+    {
+        assert!(m != T::zero(), "pow_mod: the modulus must be nonzero");
+        let r = |x: T| residue(x, m);
+        let base = Matrix22 {
+            ul: Just(r(self.ul.0)), ur: Just(r(self.ur.0)),
+            ll: Just(r(self.ll.0)), lr: Just(r(self.lr.0)),
+        };
+        let dot = |a: T, b: T, c: T, d: T| r(a * b % m + c * d % m);
+        let mul = |a: &Self, b: &Self| Matrix22 {
+            ul: Just(dot(a.ul.0, b.ul.0, a.ur.0, b.ll.0)), ur: Just(dot(a.ul.0, b.ur.0, a.ur.0, b.lr.0)),
+            ll: Just(dot(a.ll.0, b.ul.0, a.lr.0, b.ll.0)), lr: Just(dot(a.ll.0, b.ur.0, a.lr.0, b.lr.0)),
+        };
+        let power = base.pow_by(n, mul);
+        Matrix22 {
+            ul: Just(r(power.ul.0)), ur: Just(r(power.ur.0)),
+            ll: Just(r(power.ll.0)), lr: Just(r(power.lr.0)),
+        }
+    }
+
+    fn pow_by<F: Fn(&Self, &Self) -> Self>(&self, mut n: u64, mul: F) -> Self {
+        let mut result = Self::identity();
+        let mut square = self.clone();
+        while n > 0 {
+            if n & 1 == 1 {
+                result = mul(&result, &square);
+            }
+            n >>= 1;
+            if n > 0 {
+                square = mul(&square, &square);
+            }
+        }
+        result
+    }
+}
+
+// `x` reduced into `[0, m)`, even when `x` is negative.
+fn residue<T: Ring + Rem<Output = T>>(x: T, m: T) -> T {
+    (x % m + m) % m
+}
+
+/// The order-2 linear recurrence `a(n) = p a(n - 1) + q a(n - 2)`, starting
+/// from `a(0)` and `a(1)`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinearRecurrence<T> {
+    pub p: T,
+    pub q: T,
+    pub a0: T,
+    pub a1: T,
+}
+impl<T: Ring> LinearRecurrence<T> {
+    pub fn fibonacci() -> Self {
+        LinearRecurrence { p: T::one(), q: T::one(), a0: T::zero(), a1: T::one() }
+    }
+
+    // [p, q; 1, 0]^n takes (a(1), a(0)) to (a(n + 1), a(n)).
+    fn step(&self) -> Matrix2<T> {
+        Matrix22 {
+            ul: Just(self.p), ur: Just(self.q),
+            ll: Just(T::one()), lr: Just(T::zero()),
+        }
+    }
+    fn apply(&self, power: &Matrix2<T>) -> T {
+        (power * &Vector2 { x: Just(self.a1), y: Just(self.a0) }).y.0
+    }
+
+    /// The `n`th term, in `O(log n)` multiplications.
+    pub fn nth(&self, n: u64) -> T {
+        self.apply(&self.step().pow(n))
+    }
+
+    /// The `n`th term reduced into `[0, m)`, with the same bound on `m` as
+    /// `Matrix2::pow_mod`.
+    ///
+    /// # Panics
+    ///
+    /// If `m` is zero.
+    pub fn nth_mod(&self, n: u64, m: T) -> T
+    where
+        T: Rem<Output = T> + PartialEq,
+    {
+        let power = self.step().pow_mod(n, m);
+        residue(power.ll.0 * residue(self.a1, m) % m + power.lr.0 * residue(self.a0, m) % m, m)
+    }
+}
+
+/// The entry type of `-x`.
+pub type Negated<X> = <X as Neg>::Output;
+/// The entry type of `x` scaled by a `Just<T>`.
+pub type Scaled<X, T> = <X as Mul<Just<T>>>::Output;
+/// The type of `ul lr - ur ll` for the given entry types.
+pub type Determinant22<UL, UR, LL, LR> =
+    <<UL as Mul<LR>>::Output as Add<Negated<<UR as Mul<LL>>::Output>>>::Output;
+
+impl<UL: Clone, UR: Clone, LL: Clone, LR: Clone> Matrix22<UL, UR, LL, LR> {
+    pub fn transpose(&self) -> Matrix22<UL, LL, UR, LR> {
+        Matrix22 {
+            ul: self.ul.clone(), ur: self.ll.clone(),
+            ll: self.ur.clone(), lr: self.lr.clone(),
+        }
+    }
+
+    /// `ul lr - ur ll`. `Nil` entries drop out at compile time, so the
+    /// determinant of a diagonal or triangular matrix is a single multiply.
+    pub fn det(&self) -> Determinant22<UL, UR, LL, LR>
+    where
+        UL: Mul<LR>,
+        UR: Mul<LL>,
+        <UR as Mul<LL>>::Output: Neg,
+        <UL as Mul<LR>>::Output: Add<Negated<<UR as Mul<LL>>::Output>>,
+    {
+        self.ul.clone() * self.lr.clone() + -(self.ur.clone() * self.ll.clone())
+    }
+
+    /// `[lr, -ur; -ll, ul]`, so that `m * m.adjugate()` is `m.det()` times
+    /// the identity.
+    pub fn adjugate(&self) -> Matrix22<LR, Negated<UR>, Negated<LL>, UL>
+    where
+        UR: Neg,
+        LL: Neg,
+    {
+        Matrix22 {
+            ul: self.lr.clone(), ur: -self.ur.clone(),
+            ll: -self.ll.clone(), lr: self.ul.clone(),
+        }
+    }
+
+    /// The adjugate divided by the determinant, or `None` if the determinant
+    /// is exactly zero. Entries that are `Nil` in the adjugate stay `Nil`.
+    ///
+    /// Unlike [`Lu::new`] there is no rounding tolerance, so a matrix that is
+    /// singular up to rounding error gets an inverse with huge entries. Use
+    /// `lu` on a dense `Matrix2` to reject those.
+    #[allow(clippy::type_complexity)]
+    pub fn inverse<T: Real>(&self) -> Option<
+        Matrix22<Scaled<LR, T>, Scaled<Negated<UR>, T>, Scaled<Negated<LL>, T>, Scaled<UL, T>>
+    >
+    where
+        UL: Mul<LR> + Mul<Just<T>>,
+        UR: Mul<LL> + Neg,
+        LL: Neg,
+        LR: Mul<Just<T>>,
+        <UR as Mul<LL>>::Output: Neg,
+        <UL as Mul<LR>>::Output: Add<Negated<<UR as Mul<LL>>::Output>, Output = Just<T>>,
+        Negated<UR>: Mul<Just<T>>,
+        Negated<LL>: Mul<Just<T>>,
+    {
+        let det = self.det().0;
+        if det == T::zero() {
+            return None;
+        }
+        let scale = Just(T::one() / det);
+        let adjugate = self.adjugate();
+        Some(Matrix22 {
+            ul: adjugate.ul * scale, ur: adjugate.ur * scale,
+            ll: adjugate.ll * scale, lr: adjugate.lr * scale,
+        })
+    }
+
+    pub fn trace(&self) -> <UL as Add<LR>>::Output
+    where
+        UL: Add<LR>,
+    {
+        self.ul.clone() + self.lr.clone()
+    }
+
+    /// The Kronecker product `self ⊗ rhs`, the 4x4 matrix whose 2x2 blocks
+    /// are `rhs` scaled by each entry of `self`. Each entry's type is the
+    /// product of the two entries it came from, so padding an operator with
+    /// a sparse identity leaves `Nil` wherever the identity had one.
+    #[allow(clippy::type_complexity)]
+    pub fn kronecker<BUL: Clone, BUR: Clone, BLL: Clone, BLR: Clone>(
+        &self,
+        rhs: &Matrix22<BUL, BUR, BLL, BLR>,
+    ) -> Matrix44<
+        <UL as Mul<BUL>>::Output, <UL as Mul<BUR>>::Output, <UR as Mul<BUL>>::Output, <UR as Mul<BUR>>::Output,
+        <UL as Mul<BLL>>::Output, <UL as Mul<BLR>>::Output, <UR as Mul<BLL>>::Output, <UR as Mul<BLR>>::Output,
+        <LL as Mul<BUL>>::Output, <LL as Mul<BUR>>::Output, <LR as Mul<BUL>>::Output, <LR as Mul<BUR>>::Output,
+        <LL as Mul<BLL>>::Output, <LL as Mul<BLR>>::Output, <LR as Mul<BLL>>::Output, <LR as Mul<BLR>>::Output,
+    >
+    where
+        UL: Mul<BUL> + Mul<BUR> + Mul<BLL> + Mul<BLR>,
+        UR: Mul<BUL> + Mul<BUR> + Mul<BLL> + Mul<BLR>,
+        LL: Mul<BUL> + Mul<BUR> + Mul<BLL> + Mul<BLR>,
+        LR: Mul<BUL> + Mul<BUR> + Mul<BLL> + Mul<BLR>,
+    {
+        let (a, b) = (self, rhs);
+        Matrix44 {
+            m11: a.ul.clone() * b.ul.clone(), m12: a.ul.clone() * b.ur.clone(),
+            m13: a.ur.clone() * b.ul.clone(), m14: a.ur.clone() * b.ur.clone(),
+            m21: a.ul.clone() * b.ll.clone(), m22: a.ul.clone() * b.lr.clone(),
+            m23: a.ur.clone() * b.ll.clone(), m24: a.ur.clone() * b.lr.clone(),
+            m31: a.ll.clone() * b.ul.clone(), m32: a.ll.clone() * b.ur.clone(),
+            m33: a.lr.clone() * b.ul.clone(), m34: a.lr.clone() * b.ur.clone(),
+            m41: a.ll.clone() * b.ll.clone(), m42: a.ll.clone() * b.lr.clone(),
+            m43: a.lr.clone() * b.ll.clone(), m44: a.lr.clone() * b.lr.clone(),
+        }
+    }
+}
+
+/// A column vector, which matrices multiply from the left.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Vector2<A, B> {
+    pub(crate) x: A,
+    pub(crate) y: B,
+}
+/// A row vector, which multiplies matrices from the left.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Covector2<A, B> {
+    pub(crate) x: A,
+    pub(crate) y: B,
+}
+impl<A: Clone, B: Clone> Vector2<A, B> {
+    pub fn transpose(&self) -> Covector2<A, B> {
+        Covector2 { x: self.x.clone(), y: self.y.clone() }
+    }
+}
+impl<A: Clone, B: Clone> Covector2<A, B> {
+    pub fn transpose(&self) -> Vector2<A, B> {
+        Vector2 { x: self.x.clone(), y: self.y.clone() }
+    }
+}
+
+impl<UL: Clone, UR: Clone, LL: Clone, LR: Clone, A: Clone, B: Clone>
+    Mul<&Vector2<A, B>> for &Matrix22<UL, UR, LL, LR>
+where
+    ((UL, UR), (A, B)): DotProduct,
+    ((LL, LR), (A, B)): DotProduct,
+{
+    type Output = Vector2<
+        <((UL, UR), (A, B)) as DotProduct>::Output,
+        <((LL, LR), (A, B)) as DotProduct>::Output,
+    >;
+
+    fn mul(self, rhs: &Vector2<A, B>) -> Self::Output {
+        Vector2 {
+            x: ((self.ul.clone(), self.ur.clone()), (rhs.x.clone(), rhs.y.clone())).value(),
+            y: ((self.ll.clone(), self.lr.clone()), (rhs.x.clone(), rhs.y.clone())).value(),
+        }
+    }
+}
+impl<UL: Clone, UR: Clone, LL: Clone, LR: Clone, A: Clone, B: Clone>
+    Mul<&Matrix22<UL, UR, LL, LR>> for &Covector2<A, B>
+where
+    ((A, B), (UL, LL)): DotProduct,
+    ((A, B), (UR, LR)): DotProduct,
+{
+    type Output = Covector2<
+        <((A, B), (UL, LL)) as DotProduct>::Output,
+        <((A, B), (UR, LR)) as DotProduct>::Output,
+    >;
+
+    fn mul(self, rhs: &Matrix22<UL, UR, LL, LR>) -> Self::Output {
+        Covector2 {
+            x: ((self.x.clone(), self.y.clone()), (rhs.ul.clone(), rhs.ll.clone())).value(),
+            y: ((self.x.clone(), self.y.clone()), (rhs.ur.clone(), rhs.lr.clone())).value(),
+        }
+    }
+}
+/// A row vector times a column vector is their dot product.
+impl<A1: Clone, B1: Clone, A2: Clone, B2: Clone> Mul<&Vector2<A2, B2>> for &Covector2<A1, B1>
+where
+    ((A1, B1), (A2, B2)): DotProduct,
+{
+    type Output = <((A1, B1), (A2, B2)) as DotProduct>::Output;
+
+    fn mul(self, rhs: &Vector2<A2, B2>) -> Self::Output {
+        ((self.x.clone(), self.y.clone()), (rhs.x.clone(), rhs.y.clone())).value()
+    }
+}
+
+/// An affine map of the plane in homogeneous coordinates. The bottom row is
+/// always `(0, 0, 1)`, so its zeros are `Nil` and cost nothing to multiply.
+pub type Affine2<T> = Matrix33<
+    Just<T>, Just<T>, Just<T>,
+    Just<T>, Just<T>, Just<T>,
+    Nil, Nil, Just<T>,
+>;
+/// An affine map of space in homogeneous coordinates.
+pub type Affine3<T> = Matrix44<
+    Just<T>, Just<T>, Just<T>, Just<T>,
+    Just<T>, Just<T>, Just<T>, Just<T>,
+    Just<T>, Just<T>, Just<T>, Just<T>,
+    Nil, Nil, Nil, Just<T>,
+>;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Matrix33<M11, M12, M13, M21, M22, M23, M31, M32, M33> {
+    pub(crate) m11: M11, pub(crate) m12: M12, pub(crate) m13: M13,
+    pub(crate) m21: M21, pub(crate) m22: M22, pub(crate) m23: M23,
+    pub(crate) m31: M31, pub(crate) m32: M32, pub(crate) m33: M33,
+}
+impl<
+    A11: Clone, A12: Clone, A13: Clone, A21: Clone, A22: Clone, A23: Clone, A31: Clone, A32: Clone, A33: Clone,
+    B11: Clone, B12: Clone, B13: Clone, B21: Clone, B22: Clone, B23: Clone, B31: Clone, B32: Clone, B33: Clone,
+>
+    Mul<&Matrix33<B11, B12, B13, B21, B22, B23, B31, B32, B33>> for &Matrix33<A11, A12, A13, A21, A22, A23, A31, A32, A33>
+where
+    ((A11, A12, A13), (B11, B21, B31)): DotProduct,
+    ((A11, A12, A13), (B12, B22, B32)): DotProduct,
+    ((A11, A12, A13), (B13, B23, B33)): DotProduct,
+    ((A21, A22, A23), (B11, B21, B31)): DotProduct,
+    ((A21, A22, A23), (B12, B22, B32)): DotProduct,
+    ((A21, A22, A23), (B13, B23, B33)): DotProduct,
+    ((A31, A32, A33), (B11, B21, B31)): DotProduct,
+    ((A31, A32, A33), (B12, B22, B32)): DotProduct,
+    ((A31, A32, A33), (B13, B23, B33)): DotProduct,
+{
+    type Output = Matrix33<
+        <((A11, A12, A13), (B11, B21, B31)) as DotProduct>::Output,
+        <((A11, A12, A13), (B12, B22, B32)) as DotProduct>::Output,
+        <((A11, A12, A13), (B13, B23, B33)) as DotProduct>::Output,
+        <((A21, A22, A23), (B11, B21, B31)) as DotProduct>::Output,
+        <((A21, A22, A23), (B12, B22, B32)) as DotProduct>::Output,
+        <((A21, A22, A23), (B13, B23, B33)) as DotProduct>::Output,
+        <((A31, A32, A33), (B11, B21, B31)) as DotProduct>::Output,
+        <((A31, A32, A33), (B12, B22, B32)) as DotProduct>::Output,
+        <((A31, A32, A33), (B13, B23, B33)) as DotProduct>::Output,
+    >;
+
+    fn mul(self, rhs: &Matrix33<B11, B12, B13, B21, B22, B23, B31, B32, B33>) -> Self::Output {
+        Matrix33 {
+            m11: ((self.m11.clone(), self.m12.clone(), self.m13.clone()), (rhs.m11.clone(), rhs.m21.clone(), rhs.m31.clone())).value(),
+            m12: ((self.m11.clone(), self.m12.clone(), self.m13.clone()), (rhs.m12.clone(), rhs.m22.clone(), rhs.m32.clone())).value(),
+            m13: ((self.m11.clone(), self.m12.clone(), self.m13.clone()), (rhs.m13.clone(), rhs.m23.clone(), rhs.m33.clone())).value(),
+            m21: ((self.m21.clone(), self.m22.clone(), self.m23.clone()), (rhs.m11.clone(), rhs.m21.clone(), rhs.m31.clone())).value(),
+            m22: ((self.m21.clone(), self.m22.clone(), self.m23.clone()), (rhs.m12.clone(), rhs.m22.clone(), rhs.m32.clone())).value(),
+            m23: ((self.m21.clone(), self.m22.clone(), self.m23.clone()), (rhs.m13.clone(), rhs.m23.clone(), rhs.m33.clone())).value(),
+            m31: ((self.m31.clone(), self.m32.clone(), self.m33.clone()), (rhs.m11.clone(), rhs.m21.clone(), rhs.m31.clone())).value(),
+            m32: ((self.m31.clone(), self.m32.clone(), self.m33.clone()), (rhs.m12.clone(), rhs.m22.clone(), rhs.m32.clone())).value(),
+            m33: ((self.m31.clone(), self.m32.clone(), self.m33.clone()), (rhs.m13.clone(), rhs.m23.clone(), rhs.m33.clone())).value(),
+        }
+    }
+}
+impl<
+    A11: Clone, A12: Clone, A13: Clone, A21: Clone, A22: Clone, A23: Clone, A31: Clone, A32: Clone, A33: Clone,
+    B11: Clone, B12: Clone, B13: Clone, B21: Clone, B22: Clone, B23: Clone, B31: Clone, B32: Clone, B33: Clone,
+>
+    Add<&Matrix33<B11, B12, B13, B21, B22, B23, B31, B32, B33>> for &Matrix33<A11, A12, A13, A21, A22, A23, A31, A32, A33>
+where
+    A11: Add<B11>,
+    A12: Add<B12>,
+    A13: Add<B13>,
+    A21: Add<B21>,
+    A22: Add<B22>,
+    A23: Add<B23>,
+    A31: Add<B31>,
+    A32: Add<B32>,
+    A33: Add<B33>,
+{
+    type Output = Matrix33<
+        <A11 as Add<B11>>::Output,
+        <A12 as Add<B12>>::Output,
+        <A13 as Add<B13>>::Output,
+        <A21 as Add<B21>>::Output,
+        <A22 as Add<B22>>::Output,
+        <A23 as Add<B23>>::Output,
+        <A31 as Add<B31>>::Output,
+        <A32 as Add<B32>>::Output,
+        <A33 as Add<B33>>::Output,
+    >;
+
+    fn add(self, rhs: &Matrix33<B11, B12, B13, B21, B22, B23, B31, B32, B33>) -> Self::Output {
+        Matrix33 {
+            m11: self.m11.clone() + rhs.m11.clone(),
+            m12: self.m12.clone() + rhs.m12.clone(),
+            m13: self.m13.clone() + rhs.m13.clone(),
+            m21: self.m21.clone() + rhs.m21.clone(),
+            m22: self.m22.clone() + rhs.m22.clone(),
+            m23: self.m23.clone() + rhs.m23.clone(),
+            m31: self.m31.clone() + rhs.m31.clone(),
+            m32: self.m32.clone() + rhs.m32.clone(),
+            m33: self.m33.clone() + rhs.m33.clone(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Matrix44<M11, M12, M13, M14, M21, M22, M23, M24, M31, M32, M33, M34, M41, M42, M43, M44> {
+    pub(crate) m11: M11, pub(crate) m12: M12, pub(crate) m13: M13, pub(crate) m14: M14,
+    pub(crate) m21: M21, pub(crate) m22: M22, pub(crate) m23: M23, pub(crate) m24: M24,
+    pub(crate) m31: M31, pub(crate) m32: M32, pub(crate) m33: M33, pub(crate) m34: M34,
+    pub(crate) m41: M41, pub(crate) m42: M42, pub(crate) m43: M43, pub(crate) m44: M44,
+}
+impl<
+    A11: Clone, A12: Clone, A13: Clone, A14: Clone, A21: Clone, A22: Clone, A23: Clone, A24: Clone, A31: Clone, A32: Clone, A33: Clone, A34: Clone, A41: Clone, A42: Clone, A43: Clone, A44: Clone,
+    B11: Clone, B12: Clone, B13: Clone, B14: Clone, B21: Clone, B22: Clone, B23: Clone, B24: Clone, B31: Clone, B32: Clone, B33: Clone, B34: Clone, B41: Clone, B42: Clone, B43: Clone, B44: Clone,
+>
+    Mul<&Matrix44<B11, B12, B13, B14, B21, B22, B23, B24, B31, B32, B33, B34, B41, B42, B43, B44>> for &Matrix44<A11, A12, A13, A14, A21, A22, A23, A24, A31, A32, A33, A34, A41, A42, A43, A44>
+where
+    ((A11, A12, A13, A14), (B11, B21, B31, B41)): DotProduct,
+    ((A11, A12, A13, A14), (B12, B22, B32, B42)): DotProduct,
+    ((A11, A12, A13, A14), (B13, B23, B33, B43)): DotProduct,
+    ((A11, A12, A13, A14), (B14, B24, B34, B44)): DotProduct,
+    ((A21, A22, A23, A24), (B11, B21, B31, B41)): DotProduct,
+    ((A21, A22, A23, A24), (B12, B22, B32, B42)): DotProduct,
+    ((A21, A22, A23, A24), (B13, B23, B33, B43)): DotProduct,
+    ((A21, A22, A23, A24), (B14, B24, B34, B44)): DotProduct,
+    ((A31, A32, A33, A34), (B11, B21, B31, B41)): DotProduct,
+    ((A31, A32, A33, A34), (B12, B22, B32, B42)): DotProduct,
+    ((A31, A32, A33, A34), (B13, B23, B33, B43)): DotProduct,
+    ((A31, A32, A33, A34), (B14, B24, B34, B44)): DotProduct,
+    ((A41, A42, A43, A44), (B11, B21, B31, B41)): DotProduct,
+    ((A41, A42, A43, A44), (B12, B22, B32, B42)): DotProduct,
+    ((A41, A42, A43, A44), (B13, B23, B33, B43)): DotProduct,
+    ((A41, A42, A43, A44), (B14, B24, B34, B44)): DotProduct,
+{
+    type Output = Matrix44<
+        <((A11, A12, A13, A14), (B11, B21, B31, B41)) as DotProduct>::Output,
+        <((A11, A12, A13, A14), (B12, B22, B32, B42)) as DotProduct>::Output,
+        <((A11, A12, A13, A14), (B13, B23, B33, B43)) as DotProduct>::Output,
+        <((A11, A12, A13, A14), (B14, B24, B34, B44)) as DotProduct>::Output,
+        <((A21, A22, A23, A24), (B11, B21, B31, B41)) as DotProduct>::Output,
+        <((A21, A22, A23, A24), (B12, B22, B32, B42)) as DotProduct>::Output,
+        <((A21, A22, A23, A24), (B13, B23, B33, B43)) as DotProduct>::Output,
+        <((A21, A22, A23, A24), (B14, B24, B34, B44)) as DotProduct>::Output,
+        <((A31, A32, A33, A34), (B11, B21, B31, B41)) as DotProduct>::Output,
+        <((A31, A32, A33, A34), (B12, B22, B32, B42)) as DotProduct>::Output,
+        <((A31, A32, A33, A34), (B13, B23, B33, B43)) as DotProduct>::Output,
+        <((A31, A32, A33, A34), (B14, B24, B34, B44)) as DotProduct>::Output,
+        <((A41, A42, A43, A44), (B11, B21, B31, B41)) as DotProduct>::Output,
+        <((A41, A42, A43, A44), (B12, B22, B32, B42)) as DotProduct>::Output,
+        <((A41, A42, A43, A44), (B13, B23, B33, B43)) as DotProduct>::Output,
+        <((A41, A42, A43, A44), (B14, B24, B34, B44)) as DotProduct>::Output,
+    >;
+
+    fn mul(self, rhs: &Matrix44<B11, B12, B13, B14, B21, B22, B23, B24, B31, B32, B33, B34, B41, B42, B43, B44>) -> Self::Output {
+        Matrix44 {
+            m11: ((self.m11.clone(), self.m12.clone(), self.m13.clone(), self.m14.clone()), (rhs.m11.clone(), rhs.m21.clone(), rhs.m31.clone(), rhs.m41.clone())).value(),
+            m12: ((self.m11.clone(), self.m12.clone(), self.m13.clone(), self.m14.clone()), (rhs.m12.clone(), rhs.m22.clone(), rhs.m32.clone(), rhs.m42.clone())).value(),
+            m13: ((self.m11.clone(), self.m12.clone(), self.m13.clone(), self.m14.clone()), (rhs.m13.clone(), rhs.m23.clone(), rhs.m33.clone(), rhs.m43.clone())).value(),
+            m14: ((self.m11.clone(), self.m12.clone(), self.m13.clone(), self.m14.clone()), (rhs.m14.clone(), rhs.m24.clone(), rhs.m34.clone(), rhs.m44.clone())).value(),
+            m21: ((self.m21.clone(), self.m22.clone(), self.m23.clone(), self.m24.clone()), (rhs.m11.clone(), rhs.m21.clone(), rhs.m31.clone(), rhs.m41.clone())).value(),
+            m22: ((self.m21.clone(), self.m22.clone(), self.m23.clone(), self.m24.clone()), (rhs.m12.clone(), rhs.m22.clone(), rhs.m32.clone(), rhs.m42.clone())).value(),
+            m23: ((self.m21.clone(), self.m22.clone(), self.m23.clone(), self.m24.clone()), (rhs.m13.clone(), rhs.m23.clone(), rhs.m33.clone(), rhs.m43.clone())).value(),
+            m24: ((self.m21.clone(), self.m22.clone(), self.m23.clone(), self.m24.clone()), (rhs.m14.clone(), rhs.m24.clone(), rhs.m34.clone(), rhs.m44.clone())).value(),
+            m31: ((self.m31.clone(), self.m32.clone(), self.m33.clone(), self.m34.clone()), (rhs.m11.clone(), rhs.m21.clone(), rhs.m31.clone(), rhs.m41.clone())).value(),
+            m32: ((self.m31.clone(), self.m32.clone(), self.m33.clone(), self.m34.clone()), (rhs.m12.clone(), rhs.m22.clone(), rhs.m32.clone(), rhs.m42.clone())).value(),
+            m33: ((self.m31.clone(), self.m32.clone(), self.m33.clone(), self.m34.clone()), (rhs.m13.clone(), rhs.m23.clone(), rhs.m33.clone(), rhs.m43.clone())).value(),
+            m34: ((self.m31.clone(), self.m32.clone(), self.m33.clone(), self.m34.clone()), (rhs.m14.clone(), rhs.m24.clone(), rhs.m34.clone(), rhs.m44.clone())).value(),
+            m41: ((self.m41.clone(), self.m42.clone(), self.m43.clone(), self.m44.clone()), (rhs.m11.clone(), rhs.m21.clone(), rhs.m31.clone(), rhs.m41.clone())).value(),
+            m42: ((self.m41.clone(), self.m42.clone(), self.m43.clone(), self.m44.clone()), (rhs.m12.clone(), rhs.m22.clone(), rhs.m32.clone(), rhs.m42.clone())).value(),
+            m43: ((self.m41.clone(), self.m42.clone(), self.m43.clone(), self.m44.clone()), (rhs.m13.clone(), rhs.m23.clone(), rhs.m33.clone(), rhs.m43.clone())).value(),
+            m44: ((self.m41.clone(), self.m42.clone(), self.m43.clone(), self.m44.clone()), (rhs.m14.clone(), rhs.m24.clone(), rhs.m34.clone(), rhs.m44.clone())).value(),
+        }
+    }
+}
+impl<
+    A11: Clone, A12: Clone, A13: Clone, A14: Clone, A21: Clone, A22: Clone, A23: Clone, A24: Clone, A31: Clone, A32: Clone, A33: Clone, A34: Clone, A41: Clone, A42: Clone, A43: Clone, A44: Clone,
+    B11: Clone, B12: Clone, B13: Clone, B14: Clone, B21: Clone, B22: Clone, B23: Clone, B24: Clone, B31: Clone, B32: Clone, B33: Clone, B34: Clone, B41: Clone, B42: Clone, B43: Clone, B44: Clone,
+>
+    Add<&Matrix44<B11, B12, B13, B14, B21, B22, B23, B24, B31, B32, B33, B34, B41, B42, B43, B44>> for &Matrix44<A11, A12, A13, A14, A21, A22, A23, A24, A31, A32, A33, A34, A41, A42, A43, A44>
+where
+    A11: Add<B11>,
+    A12: Add<B12>,
+    A13: Add<B13>,
+    A14: Add<B14>,
+    A21: Add<B21>,
+    A22: Add<B22>,
+    A23: Add<B23>,
+    A24: Add<B24>,
+    A31: Add<B31>,
+    A32: Add<B32>,
+    A33: Add<B33>,
+    A34: Add<B34>,
+    A41: Add<B41>,
+    A42: Add<B42>,
+    A43: Add<B43>,
+    A44: Add<B44>,
+{
+    type Output = Matrix44<
+        <A11 as Add<B11>>::Output,
+        <A12 as Add<B12>>::Output,
+        <A13 as Add<B13>>::Output,
+        <A14 as Add<B14>>::Output,
+        <A21 as Add<B21>>::Output,
+        <A22 as Add<B22>>::Output,
+        <A23 as Add<B23>>::Output,
+        <A24 as Add<B24>>::Output,
+        <A31 as Add<B31>>::Output,
+        <A32 as Add<B32>>::Output,
+        <A33 as Add<B33>>::Output,
+        <A34 as Add<B34>>::Output,
+        <A41 as Add<B41>>::Output,
+        <A42 as Add<B42>>::Output,
+        <A43 as Add<B43>>::Output,
+        <A44 as Add<B44>>::Output,
+    >;
+
+    fn add(self, rhs: &Matrix44<B11, B12, B13, B14, B21, B22, B23, B24, B31, B32, B33, B34, B41, B42, B43, B44>) -> Self::Output {
+        Matrix44 {
+            m11: self.m11.clone() + rhs.m11.clone(),
+            m12: self.m12.clone() + rhs.m12.clone(),
+            m13: self.m13.clone() + rhs.m13.clone(),
+            m14: self.m14.clone() + rhs.m14.clone(),
+            m21: self.m21.clone() + rhs.m21.clone(),
+            m22: self.m22.clone() + rhs.m22.clone(),
+            m23: self.m23.clone() + rhs.m23.clone(),
+            m24: self.m24.clone() + rhs.m24.clone(),
+            m31: self.m31.clone() + rhs.m31.clone(),
+            m32: self.m32.clone() + rhs.m32.clone(),
+            m33: self.m33.clone() + rhs.m33.clone(),
+            m34: self.m34.clone() + rhs.m34.clone(),
+            m41: self.m41.clone() + rhs.m41.clone(),
+            m42: self.m42.clone() + rhs.m42.clone(),
+            m43: self.m43.clone() + rhs.m43.clone(),
+            m44: self.m44.clone() + rhs.m44.clone(),
+        }
+    }
+}
+
+
+impl<M11: Clone, M12, M13, M21, M22: Clone, M23, M31, M32, M33: Clone>
+    Matrix33<M11, M12, M13, M21, M22, M23, M31, M32, M33>
+{
+    pub fn trace(&self) -> <<M11 as Add<M22>>::Output as Add<M33>>::Output
+    where
+        M11: Add<M22>,
+        <M11 as Add<M22>>::Output: Add<M33>,
+    {
+        self.m11.clone() + self.m22.clone() + self.m33.clone()
+    }
+}
+impl<M11: Clone, M12, M13, M14, M21, M22: Clone, M23, M24, M31, M32, M33: Clone, M34, M41, M42, M43, M44: Clone>
+    Matrix44<M11, M12, M13, M14, M21, M22, M23, M24, M31, M32, M33, M34, M41, M42, M43, M44>
+{
+    #[allow(clippy::type_complexity)]
+    pub fn trace(&self) -> <<<M11 as Add<M22>>::Output as Add<M33>>::Output as Add<M44>>::Output
+    where
+        M11: Add<M22>,
+        <M11 as Add<M22>>::Output: Add<M33>,
+        <<M11 as Add<M22>>::Output as Add<M33>>::Output: Add<M44>,
+    {
+        self.m11.clone() + self.m22.clone() + self.m33.clone() + self.m44.clone()
+    }
+}
+
+pub type Matrix3<T> = Matrix33<
+    Just<T>, Just<T>, Just<T>,
+    Just<T>, Just<T>, Just<T>,
+    Just<T>, Just<T>, Just<T>,
+>;
+pub type Matrix4<T> = Matrix44<
+    Just<T>, Just<T>, Just<T>, Just<T>,
+    Just<T>, Just<T>, Just<T>, Just<T>,
+    Just<T>, Just<T>, Just<T>, Just<T>,
+    Just<T>, Just<T>, Just<T>, Just<T>,
+>;
+
+/// The eigenvalues of a symmetric 2x2 matrix in ascending order, with unit
+/// eigenvectors in the same order.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SymmetricEigen2<T> {
+    pub values: [T; 2],
+    pub vectors: [Vector2<Just<T>, Just<T>>; 2],
+}
+
+/// The eigenvalues of a symmetric 3x3 matrix in ascending order, with unit
+/// eigenvectors in the same order. The eigenvectors form a right-handed
+/// frame, and `rotation` is the rotor taking e1, e2 and e3 onto them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SymmetricEigen3<T> {
+    pub values: [T; 3],
+    pub vectors: [Vec3<T>; 3],
+    pub rotation: Rotor<T>,
+}
+
+const MAX_JACOBI_SWEEPS: usize = 64;
+
+fn abs<T: Real>(x: T) -> T {
+    if x < T::zero() { -x } else { x }
+}
+
+/// The Jacobi rotation `(c, s)` that zeroes `a[p][q]` of a symmetric matrix,
+/// given `a[p][p]`, `a[q][q]` and a non-zero `a[p][q]`. With `J` the identity
+/// except for `J[p][p] = J[q][q] = c` and `J[p][q] = -J[q][p] = s`, `J^T A J`
+/// has `a[p][p] - t a[p][q]` and `a[q][q] + t a[p][q]` on the diagonal, where
+/// `t = s / c` is also returned.
+fn jacobi_rotation<T: Real>(app: T, aqq: T, apq: T) -> (T, T, T) {
+    let theta = (aqq - app) / (apq + apq);
+    let magnitude = T::one() / (abs(theta) + (theta * theta + T::one()).sqrt());
+    let t = if theta < T::zero() { -magnitude } else { magnitude };
+    let c = T::one() / (t * t + T::one()).sqrt();
+    (c, t * c, t)
+}
+
+fn ascending<T: Real, V>(pairs: &mut [(T, V)]) {
+    pairs.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(Ordering::Equal));
+}
+
+impl<T: Real> Matrix2<T> {
+    /// The eigendecomposition of a symmetric matrix, in closed form by a
+    /// single Jacobi rotation. `None` if the off-diagonal entries differ by
+    /// more than `tolerance`.
+    pub fn symmetric_eigen(&self, tolerance: T) -> Option<SymmetricEigen2<T>> {
+        let (a, b, d) = (self.ul.0, self.ur.0, self.lr.0);
+        if abs(b - self.ll.0) > tolerance {
+            return None;
+        }
+        let vector = |x, y| Vector2 { x: Just(x), y: Just(y) };
+        let mut pairs = if b == T::zero() {
+            [(a, vector(T::one(), T::zero())), (d, vector(T::zero(), T::one()))]
+        } else {
+            let (c, s, t) = jacobi_rotation(a, d, b);
+            [(a - t * b, vector(c, -s)), (d + t * b, vector(s, c))]
+        };
+        ascending(&mut pairs);
+        let [(v0, x0), (v1, x1)] = pairs;
+        Some(SymmetricEigen2 { values: [v0, v1], vectors: [x0, x1] })
+    }
+}
+
+impl<T: Real> Matrix3<T> {
+    fn rows(&self) -> [[T; 3]; 3] {
+        [
+            [self.m11.0, self.m12.0, self.m13.0],
+            [self.m21.0, self.m22.0, self.m23.0],
+            [self.m31.0, self.m32.0, self.m33.0],
+        ]
+    }
+
+    /// The eigendecomposition of a symmetric matrix by cyclic Jacobi
+    /// rotations, stopping once every off-diagonal entry is within
+    /// `tolerance` of zero. `None` if the matrix is not symmetric to within
+    /// `tolerance`, or if it has not converged after a generous number of
+    /// sweeps.
+    pub fn symmetric_eigen(&self, tolerance: T) -> Option<SymmetricEigen3<T>> {
+        const PAIRS: [(usize, usize); 3] = [(0, 1), (0, 2), (1, 2)];
+        let mut a = self.rows();
+        if PAIRS.iter().any(|&(p, q)| abs(a[p][q] - a[q][p]) > tolerance) {
+            return None;
+        }
+        let (zero, one) = (T::zero(), T::one());
+        let mut v = [[one, zero, zero], [zero, one, zero], [zero, zero, one]];
+        let off_diagonal = |a: &[[T; 3]; 3]| PAIRS.iter().all(|&(p, q)| abs(a[p][q]) <= tolerance);
+        let mut sweeps = 0;
+        while !off_diagonal(&a) {
+            if sweeps == MAX_JACOBI_SWEEPS {
+                return None;
+            }
+            sweeps += 1;
+            for &(p, q) in PAIRS.iter() {
+                if a[p][q] == zero {
+                    continue;
+                }
+                let (c, s, _) = jacobi_rotation(a[p][p], a[q][q], a[p][q]);
+                // A J and V J act on columns p and q, then J^T (A J) on rows.
+                for row in a.iter_mut().chain(v.iter_mut()) {
+                    let (xp, xq) = (row[p], row[q]);
+                    row[p] = c * xp - s * xq;
+                    row[q] = s * xp + c * xq;
+                }
+                let (row_p, row_q) = (a[p], a[q]);
+                for (k, (&apk, &aqk)) in row_p.iter().zip(&row_q).enumerate() {
+                    a[p][k] = c * apk - s * aqk;
+                    a[q][k] = s * apk + c * aqk;
+                }
+            }
+        }
+
+        let column = |i: usize| Vec3::new(v[0][i], v[1][i], v[2][i]);
+        let mut pairs = [(a[0][0], column(0)), (a[1][1], column(1)), (a[2][2], column(2))];
+        ascending(&mut pairs);
+        let [(v0, x0), (v1, x1), (v2, x2)] = pairs;
+        // Flip the last vector if needed, so that the frame is a rotation.
+        let handedness = x0.wedge(&x1).wedge(&x2).e123.0;
+        let x2 = if handedness < zero { Vec3::new(-x2.e1.0, -x2.e2.0, -x2.e3.0) } else { x2 };
+        let rotation = rotor_from_frame(&x0, &x1, &x2);
+        Some(SymmetricEigen3 { values: [v0, v1, v2], vectors: [x0, x1, x2], rotation })
+    }
+}
+
+/// The rotor taking e1, e2 and e3 to the given right-handed orthonormal
+/// frame, by Shepperd's method: whichever of the four rotor components is
+/// largest is found from the diagonal, and the rest from the off-diagonal
+/// entries of the rotation matrix.
+fn rotor_from_frame<T: Real>(x: &Vec3<T>, y: &Vec3<T>, z: &Vec3<T>) -> Rotor<T> {
+    let m = [
+        [x.e1.0, y.e1.0, z.e1.0],
+        [x.e2.0, y.e2.0, z.e2.0],
+        [x.e3.0, y.e3.0, z.e3.0],
+    ];
+    let one = T::one();
+    let quarter = T::from_f64(0.25);
+    let half = T::from_f64(0.5);
+    let trace = m[0][0] + m[1][1] + m[2][2];
+    // (w, u) is the unit quaternion of the rotation. Our rotors apply as
+    // R v ~R, which with the e23, e31, e12 components (a1, a2, a3) works out to
+    // the quaternion (s, -a1, -a2, -a3).
+    let (w, u) = if trace > m[0][0] && trace > m[1][1] && trace > m[2][2] {
+        let w = half * (one + trace).sqrt();
+        let k = quarter / w;
+        (w, [(m[2][1] - m[1][2]) * k, (m[0][2] - m[2][0]) * k, (m[1][0] - m[0][1]) * k])
+    } else if m[0][0] >= m[1][1] && m[0][0] >= m[2][2] {
+        let u0 = half * (one + m[0][0] - m[1][1] - m[2][2]).sqrt();
+        let k = quarter / u0;
+        ((m[2][1] - m[1][2]) * k, [u0, (m[0][1] + m[1][0]) * k, (m[0][2] + m[2][0]) * k])
+    } else if m[1][1] >= m[2][2] {
+        let u1 = half * (one + m[1][1] - m[0][0] - m[2][2]).sqrt();
+        let k = quarter / u1;
+        ((m[0][2] - m[2][0]) * k, [(m[0][1] + m[1][0]) * k, u1, (m[1][2] + m[2][1]) * k])
+    } else {
+        let u2 = half * (one + m[2][2] - m[0][0] - m[1][1]).sqrt();
+        let k = quarter / u2;
+        ((m[1][0] - m[0][1]) * k, [(m[0][2] + m[2][0]) * k, (m[1][2] + m[2][1]) * k, u2])
     };
-    let result = &matrix_identity * &matrix_full;
-    let result = &matrix_identity * &result;
-    let result = &result * &matrix_identity;
-    let result = &matrix_identity * &result;
-    let result = &result * &matrix_identity;
-    let result = &matrix_identity * &result;
-    let result = &result * &matrix_identity;
-    let result = &matrix_identity * &result;
-    let result = &result * &matrix_identity;
-    let result = &matrix_identity * &result;
-    let result = &result * &matrix_identity;
-
-    println!("[{:?},\t {:?}]\n[{:?},\t {:?}]", &result.ul, &result.ur, &result.ll, &result.lr)
-}
\ No newline at end of file
+    Multivector3 {
+        e: Just(w),
+        e1: Nil(), e2: Nil(), e3: Nil(),
+        e12: Just(-u[2]), e31: Just(-u[1]), e23: Just(-u[0]),
+        e123: Nil(),
+    }
+}
+
+/// The error for solving a linear system whose matrix has no inverse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SingularMatrix;
+impl fmt::Display for SingularMatrix {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "matrix is singular")
+    }
+}
+impl Error for SingularMatrix {}
+
+/// The LU decomposition `P A = L U` of an `N` by `N` matrix, with partial
+/// pivoting. Factor once to solve for several right hand sides.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lu<T, const N: usize> {
+    // L strictly below the diagonal, with an implied unit diagonal, and U on
+    // and above it.
+    factors: [[T; N]; N],
+    // Row `i` of `P A` is row `permutation[i]` of `A`.
+    permutation: [usize; N],
+    odd: bool,
+}
+
+impl<T: Real, const N: usize> Lu<T, N> {
+    /// Doolittle elimination, swapping the largest remaining entry of each
+    /// column onto the diagonal. Fails if that entry is zero to within
+    /// rounding error relative to the largest entry of the matrix, which is
+    /// when the matrix is singular or too close to it to solve reliably.
+    pub fn new(rows: [[T; N]; N]) -> Result<Self, SingularMatrix> {
+        let largest = rows.iter().flatten().fold(T::zero(), |m, &x| if abs(x) > m { abs(x) } else { m });
+        let size = (0..N).fold(T::zero(), |n, _| n + T::one());
+        Lu::factor(rows, size * T::epsilon() * largest)
+    }
+
+    // Fails if a pivot is no larger than `threshold` in magnitude.
+    fn factor(rows: [[T; N]; N], threshold: T) -> Result<Self, SingularMatrix> {
+        let mut factors = rows;
+        let mut permutation = [0; N];
+        for (i, p) in permutation.iter_mut().enumerate() {
+            *p = i;
+        }
+        let mut odd = false;
+        for k in 0..N {
+            let pivot = (k..N)
+                .max_by(|&i, &j| abs(factors[i][k]).partial_cmp(&abs(factors[j][k])).unwrap_or(Ordering::Equal))
+                .unwrap_or(k);
+            if abs(factors[pivot][k]) <= threshold {
+                return Err(SingularMatrix);
+            }
+            if pivot != k {
+                factors.swap(pivot, k);
+                permutation.swap(pivot, k);
+                odd = !odd;
+            }
+            let pivot_row = factors[k];
+            for row in factors[k + 1..].iter_mut() {
+                let multiplier = row[k] / pivot_row[k];
+                row[k] = multiplier;
+                for (x, &p) in row[k + 1..].iter_mut().zip(&pivot_row[k + 1..]) {
+                    *x = *x - multiplier * p;
+                }
+            }
+        }
+        Ok(Lu { factors, permutation, odd })
+    }
+
+    pub fn det(&self) -> T {
+        let product = (0..N).fold(T::one(), |product, i| product * self.factors[i][i]);
+        if self.odd { -product } else { product }
+    }
+
+    /// The `x` with `A x = b`, by forward and back substitution.
+    pub fn solve(&self, b: &[T; N]) -> [T; N] {
+        let mut x = [T::zero(); N];
+        for i in 0..N {
+            let known = (0..i).fold(T::zero(), |sum, j| sum + self.factors[i][j] * x[j]);
+            x[i] = b[self.permutation[i]] - known;
+        }
+        for i in (0..N).rev() {
+            let known = (i + 1..N).fold(T::zero(), |sum, j| sum + self.factors[i][j] * x[j]);
+            x[i] = (x[i] - known) / self.factors[i][i];
+        }
+        x
+    }
+}
+
+impl<T: Real> Matrix2<T> {
+    fn rows(&self) -> [[T; 2]; 2] {
+        [[self.ul.0, self.ur.0], [self.ll.0, self.lr.0]]
+    }
+    pub fn lu(&self) -> Result<Lu<T, 2>, SingularMatrix> {
+        Lu::new(self.rows())
+    }
+    pub fn solve(&self, b: &[T; 2]) -> Result<[T; 2], SingularMatrix> {
+        Ok(self.lu()?.solve(b))
+    }
+}
+impl<T: Real> Matrix3<T> {
+    pub fn lu(&self) -> Result<Lu<T, 3>, SingularMatrix> {
+        Lu::new(self.rows())
+    }
+    pub fn solve(&self, b: &[T; 3]) -> Result<[T; 3], SingularMatrix> {
+        Ok(self.lu()?.solve(b))
+    }
+    /// The product of the pivots, however small they are. Only a column that
+    /// eliminates to exact zeros gives zero.
+    pub fn det(&self) -> T {
+        Lu::factor(self.rows(), T::zero()).map_or_else(|_| T::zero(), |lu| lu.det())
+    }
+}
+impl<T: Real> Matrix4<T> {
+    fn rows(&self) -> [[T; 4]; 4] {
+        [
+            [self.m11.0, self.m12.0, self.m13.0, self.m14.0],
+            [self.m21.0, self.m22.0, self.m23.0, self.m24.0],
+            [self.m31.0, self.m32.0, self.m33.0, self.m34.0],
+            [self.m41.0, self.m42.0, self.m43.0, self.m44.0],
+        ]
+    }
+    pub fn lu(&self) -> Result<Lu<T, 4>, SingularMatrix> {
+        Lu::new(self.rows())
+    }
+    pub fn solve(&self, b: &[T; 4]) -> Result<[T; 4], SingularMatrix> {
+        Ok(self.lu()?.solve(b))
+    }
+    /// The product of the pivots, however small they are. Only a column that
+    /// eliminates to exact zeros gives zero.
+    pub fn det(&self) -> T {
+        Lu::factor(self.rows(), T::zero()).map_or_else(|_| T::zero(), |lu| lu.det())
+    }
+}
+
+// Cl(3,0) is isomorphic to the 2x2 complex matrices, taking e1, e2 and e3 to
+// the Pauli matrices [0, 1; 1, 0], [0, -i; i, 0] and [1, 0; 0, -1]. The
+// bivectors then map to i times a Pauli matrix and e123 to i times the
+// identity. The complex entries below are a plain pair of reals rather than
+// the scalar plus pseudoscalar `Complex` multivector, so that the matrix
+// product is an independent check on the `Se*` sign tables.
+
+/// `re + i im`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ComplexNumber<T> {
+    pub re: T,
+    pub im: T,
+}
+impl<T: Add<Output = T>> Add for ComplexNumber<T> {
+    type Output = Self;
+    fn add(self, rhs: Self) -> Self {
+        ComplexNumber { re: self.re + rhs.re, im: self.im + rhs.im }
+    }
+}
+impl<T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T>> Mul for ComplexNumber<T> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self {
+        ComplexNumber {
+            re: self.re * rhs.re - self.im * rhs.im,
+            im: self.re * rhs.im + self.im * rhs.re,
+        }
+    }
+}
+
+pub type PauliMatrix<T> = Matrix2n<ComplexNumber<T>>;
+
+impl<T: Copy + Add<Output = T> + Sub<Output = T>> From<&Frame<T>> for PauliMatrix<T> {
+    fn from(m: &Frame<T>) -> Self {
+        let complex = |re, im| ComplexNumber { re, im };
+        Matrix22 {
+            ul: complex(m.e.0 + m.e3.0, m.e12.0 + m.e123.0),
+            ur: complex(m.e1.0 + m.e31.0, m.e23.0 - m.e2.0),
+            ll: complex(m.e1.0 - m.e31.0, m.e2.0 + m.e23.0),
+            lr: complex(m.e.0 - m.e3.0, m.e123.0 - m.e12.0),
+        }
+    }
+}
+impl<T: Real> From<&PauliMatrix<T>> for Frame<T> {
+    fn from(m: &PauliMatrix<T>) -> Self {
+        let half = |x: T| x / (T::one() + T::one());
+        let (ul, ur, ll, lr) = (m.ul, m.ur, m.ll, m.lr);
+        Multivector3 {
+            e: Just(half(ul.re + lr.re)),
+            e1: Just(half(ur.re + ll.re)),
+            e2: Just(half(ll.im - ur.im)),
+            e3: Just(half(ul.re - lr.re)),
+            e12: Just(half(ul.im - lr.im)),
+            e31: Just(half(ur.re - ll.re)),
+            e23: Just(half(ur.im + ll.im)),
+            e123: Just(half(ul.im + lr.im)),
+        }
+    }
+}
+
+// 2x2 real matrices are a faithful representation of Cl(2,0), taking
+// e1 = [1, 0; 0, -1] and e2 = [0, 1; 1, 0], so that e12 = [0, 1; -1, 0]. The
+// blades are numbered e, e1, e2, e12, and the matrix product is the geometric
+// product.
+impl<S: Real, UL, UR, LL, LR> Multivector for Matrix22<UL, UR, LL, LR>
+where
+    UL: Component<S>, UR: Component<S>, LL: Component<S>, LR: Component<S>,
+    (UL, UR, LL, LR): JoinAll,
+    <(UL, UR, LL, LR) as JoinAll>::Output: Coefficient<Scalar = S>,
+{
+    type Scalar = S;
+
+    const BLADES: usize = 4;
+    const MASK: u32 = ((UL::PRESENT || LR::PRESENT) as u32 * 0b0011)
+        | ((UR::PRESENT || LL::PRESENT) as u32 * 0b1100);
+
+    fn grade(blade: usize) -> Option<usize> {
+        [0, 1, 1, 2].get(blade).copied()
+    }
+    fn component(&self, blade: usize) -> Option<S> {
+        let half = |a: Option<S>, b: Option<S>, sign: S| match (a, b) {
+            (None, None) => None,
+            (a, b) => {
+                let (a, b) = (a.unwrap_or_else(S::zero), b.unwrap_or_else(S::zero));
+                Some((a + sign * b) / (S::one() + S::one()))
+            }
+        };
+        let (ul, ur, ll, lr) = (self.ul.coefficient(), self.ur.coefficient(), self.ll.coefficient(), self.lr.coefficient());
+        match blade {
+            0 => half(ul, lr, S::one()),
+            1 => half(ul, lr, -S::one()),
+            2 => half(ur, ll, S::one()),
+            3 => half(ur, ll, -S::one()),
+            _ => None,
+        }
+    }
+}
+
+impl<P, Rhs, UL, UR, LL, LR> GeometricProduct<Rhs> for Matrix22<UL, UR, LL, LR>
+where
+    Self: Multivector,
+    for<'a> &'a Self: Mul<&'a Rhs, Output = P>,
+    P: Multivector,
+{
+    type Output = P;
+    fn product(&self, rhs: &Rhs) -> P {
+        self * rhs
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn matrix_as_multivector() {
+        // 1 + 2 e1 + 3 e2 + 4 e12
+        let m = Matrix22 {
+            ul: Just(3.0), ur: Just(7.0),
+            ll: Just(-1.0), lr: Just(-1.0),
+        };
+        let components: Vec<_> = (0..4).map(|blade| m.component(blade)).collect();
+        assert_eq!(components, vec![Some(1.0), Some(2.0), Some(3.0), Some(4.0)]);
+
+        let diagonal = Matrix22 {
+            ul: Just(2.0), ur: Nil(),
+            ll: Nil(), lr: Just(2.0),
+        };
+        assert_eq!(<Matrix22<Just<f64>, Nil, Nil, Just<f64>> as Multivector>::MASK, 0b0011);
+        assert_eq!(diagonal.component(1), Some(0.0));
+        assert_eq!(diagonal.component(2), None);
+
+        // e12 squares to -1.
+        let e12 = Matrix22 {
+            ul: Nil(), ur: Just(1.0),
+            ll: Just(-1.0), lr: Nil(),
+        };
+        assert_eq!(e12.product(&e12).component(0), Some(-1.0));
+    }
+
+    #[test]
+    fn affine_transforms_keep_constant_rows() {
+        // Rotate by a quarter turn, then translate by (3, 4).
+        let rotate = Matrix33 {
+            m11: Just(0), m12: Just(-1), m13: Nil(),
+            m21: Just(1), m22: Just(0), m23: Nil(),
+            m31: Nil(), m32: Nil(), m33: Just(1),
+        };
+        let translate = Matrix33 {
+            m11: Just(1), m12: Nil(), m13: Just(3),
+            m21: Nil(), m22: Just(1), m23: Just(4),
+            m31: Nil(), m32: Nil(), m33: Just(1),
+        };
+        let affine: Affine2<i32> = &translate * &rotate;
+        assert_eq!(affine, Matrix33 {
+            m11: Just(0), m12: Just(-1), m13: Just(3),
+            m21: Just(1), m22: Just(0), m23: Just(4),
+            m31: Nil(), m32: Nil(), m33: Just(1),
+        });
+        let sum = &affine + &translate;
+        assert_eq!((sum.m11, sum.m13, sum.m31, sum.m33), (Just(1), Just(6), Nil(), Just(2)));
+    }
+
+    #[test]
+    fn matrix44_product() {
+        let translate = Matrix44 {
+            m11: Just(1), m12: Nil(), m13: Nil(), m14: Just(1),
+            m21: Nil(), m22: Just(1), m23: Nil(), m24: Just(2),
+            m31: Nil(), m32: Nil(), m33: Just(1), m34: Just(3),
+            m41: Nil(), m42: Nil(), m43: Nil(), m44: Just(1),
+        };
+        let scale = Matrix44 {
+            m11: Just(2), m12: Nil(), m13: Nil(), m14: Nil(),
+            m21: Nil(), m22: Just(3), m23: Nil(), m24: Nil(),
+            m31: Nil(), m32: Nil(), m33: Just(4), m34: Nil(),
+            m41: Nil(), m42: Nil(), m43: Nil(), m44: Just(1),
+        };
+        type ScaleTranslate = Matrix44<
+            Just<i32>, Nil, Nil, Just<i32>,
+            Nil, Just<i32>, Nil, Just<i32>,
+            Nil, Nil, Just<i32>, Just<i32>,
+            Nil, Nil, Nil, Just<i32>,
+        >;
+        let product: ScaleTranslate = &translate * &scale;
+        assert_eq!((product.m11, product.m14, product.m24, product.m34), (Just(2), Just(1), Just(2), Just(3)));
+        let swapped = &scale * &translate;
+        assert_eq!((swapped.m14, swapped.m24, swapped.m34), (Just(2), Just(6), Just(12)));
+        assert_eq!(((Just(1), Just(2), Nil(), Just(4)), (Just(5), Just(6), Just(7), Nil())).value(), Just(17));
+    }
+
+    #[test]
+    fn transpose_det_and_inverse() {
+        let m = Matrix22 {
+            ul: Just(1.0), ur: Just(2.0),
+            ll: Just(3.0), lr: Just(4.0),
+        };
+        assert_eq!(m.transpose(), Matrix22 {
+            ul: Just(1.0), ur: Just(3.0),
+            ll: Just(2.0), lr: Just(4.0),
+        });
+        assert_eq!(m.det(), Just(-2.0));
+        let identity = &m * &m.inverse().unwrap();
+        assert_eq!(identity, Matrix22 {
+            ul: Just(1.0), ur: Just(0.0),
+            ll: Just(0.0), lr: Just(1.0),
+        });
+        let scaled = &m * &m.adjugate();
+        assert_eq!((scaled.ul, scaled.ur), (Just(-2.0), Just(0.0)));
+
+        let singular = Matrix22 {
+            ul: Just(1.0), ur: Just(2.0),
+            ll: Just(2.0), lr: Just(4.0),
+        };
+        assert_eq!(singular.inverse(), None);
+
+        // Only an exact zero determinant is rejected; LU applies a tolerance.
+        let nearly_singular: Matrix2<f64> = Matrix22 {
+            ul: Just(1.0), ur: Just(2.0),
+            ll: Just(1.0), lr: Just(2.0 + 4.0 * f64::EPSILON),
+        };
+        assert!(nearly_singular.inverse().is_some());
+        assert_eq!(nearly_singular.lu().err(), Some(SingularMatrix));
+    }
+
+    #[test]
+    fn sparse_entries_stay_sparse() {
+        let diagonal = Matrix22 {
+            ul: Just(2.0), ur: Nil(),
+            ll: Nil(), lr: Just(4.0),
+        };
+        let det: Just<f64> = diagonal.det();
+        assert_eq!(det, Just(8.0));
+        let inverse: Matrix22<Just<f64>, Nil, Nil, Just<f64>> = diagonal.inverse().unwrap();
+        assert_eq!(inverse, Matrix22 {
+            ul: Just(0.5), ur: Nil(),
+            ll: Nil(), lr: Just(0.25),
+        });
+
+        let upper = Matrix22 {
+            ul: Just(1), ur: Just(5),
+            ll: Nil(), lr: Just(3),
+        };
+        let transposed: Matrix22<Just<i32>, Nil, Just<i32>, Just<i32>> = upper.transpose();
+        assert_eq!(transposed.ll, Just(5));
+        let det: Just<i32> = upper.det();
+        assert_eq!(det, Just(3));
+        let adjugate: Matrix22<Just<i32>, Just<i32>, Nil, Just<i32>> = upper.adjugate();
+        assert_eq!(adjugate.ur, Just(-5));
+        assert_eq!(Matrix22 { ul: Nil(), ur: Nil(), ll: Nil(), lr: Nil() }.det(), Nil());
+    }
+
+    #[test]
+    fn matrix_vector_products() {
+        let m = Matrix22 {
+            ul: Just(1), ur: Just(2),
+            ll: Just(3), lr: Just(4),
+        };
+        let v = Vector2 { x: Just(5), y: Just(6) };
+        assert_eq!(&m * &v, Vector2 { x: Just(17), y: Just(39) });
+        assert_eq!(&v.transpose() * &m, Covector2 { x: Just(23), y: Just(34) });
+        assert_eq!(&v.transpose() * &v, Just(61));
+
+        // A shear applied to a vector along x only touches the x entry.
+        let shear = Matrix22 {
+            ul: Just(1), ur: Just(2),
+            ll: Nil(), lr: Just(1),
+        };
+        let x_axis = Vector2 { x: Just(3), y: Nil() };
+        let sheared: Vector2<Just<i32>, Nil> = &shear * &x_axis;
+        assert_eq!(sheared, x_axis);
+        let row: Covector2<Nil, Just<i32>> = &Covector2 { x: Nil(), y: Just(1) } * &shear;
+        assert_eq!(row.transpose(), Vector2 { x: Nil(), y: Just(1) });
+    }
+
+    trait Rows: Sized {
+        const SIZE: usize;
+        fn from_rows(rows: &[Vec<i64>]) -> Self;
+        fn rows(&self) -> Vec<Vec<i64>>;
+    }
+    impl Rows for Just<i64> {
+        const SIZE: usize = 1;
+        fn from_rows(rows: &[Vec<i64>]) -> Self {
+            Just(rows[0][0])
+        }
+        fn rows(&self) -> Vec<Vec<i64>> {
+            vec![vec![self.0]]
+        }
+    }
+    impl<M: Rows> Rows for Matrix2n<M> {
+        const SIZE: usize = 2 * M::SIZE;
+        fn from_rows(rows: &[Vec<i64>]) -> Self {
+            let n = M::SIZE;
+            let block = |r: usize, c: usize| {
+                let rows: Vec<_> = rows[r..r + n].iter().map(|row| row[c..c + n].to_vec()).collect();
+                M::from_rows(&rows)
+            };
+            Matrix22 {
+                ul: block(0, 0), ur: block(0, n),
+                ll: block(n, 0), lr: block(n, n),
+            }
+        }
+        fn rows(&self) -> Vec<Vec<i64>> {
+            let join = |left: Vec<Vec<i64>>, right: Vec<Vec<i64>>| {
+                left.into_iter().zip(right).map(|(l, r)| [l, r].concat()).collect::<Vec<_>>()
+            };
+            let mut rows = join(self.ul.rows(), self.ur.rows());
+            rows.extend(join(self.ll.rows(), self.lr.rows()));
+            rows
+        }
+    }
+    fn dense(size: usize, seed: i64) -> Vec<Vec<i64>> {
+        (0..size)
+            .map(|r| (0..size).map(|c| (r as i64 * 7 + c as i64 * 3 + seed) % 11 - 5).collect())
+            .collect()
+    }
+    fn naive_product(a: &[Vec<i64>], b: &[Vec<i64>]) -> Vec<Vec<i64>> {
+        let n = a.len();
+        (0..n)
+            .map(|r| (0..n).map(|c| (0..n).map(|k| a[r][k] * b[k][c]).sum()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn block_matrices() {
+        let (a, b) = (dense(4, 1), dense(4, 2));
+        let product = &Block4::from_rows(&a) * &Block4::from_rows(&b);
+        assert_eq!(product.rows(), naive_product(&a, &b));
+        let sum = Block4::from_rows(&a) + Block4::from_rows(&b);
+        assert_eq!(sum.rows()[1][2], a[1][2] + b[1][2]);
+
+        let (a, b) = (dense(8, 3), dense(8, 4));
+        let product = Block8::from_rows(&a) * Block8::from_rows(&b);
+        assert_eq!(product.rows(), naive_product(&a, &b));
+    }
+
+    #[test]
+    fn sparse_blocks() {
+        // A block-diagonal 8x8 operator only multiplies its diagonal blocks.
+        let (a, b) = (dense(4, 5), dense(4, 6));
+        let diagonal = Matrix22 {
+            ul: Block4::from_rows(&a), ur: Nil(),
+            ll: Nil(), lr: Block4::from_rows(&b),
+        };
+        let squared: Matrix22<Block4<i64>, Nil, Nil, Block4<i64>> = &diagonal * &diagonal;
+        assert_eq!(squared.ul.rows(), naive_product(&a, &a));
+        assert_eq!(squared.lr.rows(), naive_product(&b, &b));
+    }
+
+    #[test]
+    fn powers() {
+        let m = Matrix22 {
+            ul: Just(1i64), ur: Just(2),
+            ll: Just(3), lr: Just(4),
+        };
+        assert_eq!(m.pow(0), Matrix2::identity());
+        assert_eq!(m.pow(1), m);
+        let mut expected = Matrix2::identity();
+        for _ in 0..7 {
+            expected = &expected * &m;
+        }
+        assert_eq!(m.pow(7), expected);
+        assert_eq!(m.pow_mod(7, 10), Matrix22 {
+            ul: Just(expected.ul.0 % 10), ur: Just(expected.ur.0 % 10),
+            ll: Just(expected.ll.0 % 10), lr: Just(expected.lr.0 % 10),
+        });
+
+        let negative = Matrix22 {
+            ul: Just(-1i64), ur: Just(2),
+            ll: Just(-3), lr: Just(0),
+        };
+        let cube = negative.pow(3);
+        assert_eq!(negative.pow_mod(3, 10), Matrix22 {
+            ul: Just(cube.ul.0.rem_euclid(10)), ur: Just(cube.ur.0.rem_euclid(10)),
+            ll: Just(cube.ll.0.rem_euclid(10)), lr: Just(cube.lr.0.rem_euclid(10)),
+        });
+    }
+
+    #[test]
+    fn linear_recurrences() {
+        let fibonacci = LinearRecurrence::<u64>::fibonacci();
+        let first: Vec<_> = (0..10).map(|n| fibonacci.nth(n)).collect();
+        assert_eq!(first, vec![0, 1, 1, 2, 3, 5, 8, 13, 21, 34]);
+        assert_eq!(fibonacci.nth(90), 2_880_067_194_370_816_120);
+        assert_eq!(fibonacci.nth_mod(90, 1_000_000_007), 2_880_067_194_370_816_120 % 1_000_000_007);
+        // Pisano period: F(n) mod 10 repeats every 60 terms.
+        assert_eq!(fibonacci.nth_mod(1_000_000_000_000_060, 10), fibonacci.nth_mod(1_000_000_000_000_000, 10));
+
+        // Lucas numbers, and a(n) = 2 a(n - 1) + 3 a(n - 2) = (3^(n+1) + (-1)^n) / 4.
+        let lucas = LinearRecurrence { p: 1i64, q: 1, a0: 2, a1: 1 };
+        assert_eq!(lucas.nth(10), 123);
+        let threes = LinearRecurrence { p: 2i64, q: 3, a0: 1, a1: 2 };
+        assert_eq!(threes.nth(10), (3i64.pow(11) + 1) / 4);
+        assert_eq!(threes.nth_mod(10, 1000), (3i64.pow(11) + 1) / 4 % 1000);
+
+        // (m - 1)^2 fits in a u64 but the sum of two such products does not.
+        let m = 4_294_967_291;
+        let wide = LinearRecurrence::<u128>::fibonacci();
+        for &n in &[1000, 123_456_789] {
+            assert_eq!(u128::from(fibonacci.nth_mod(n, m)), wide.nth_mod(n, u128::from(m)));
+        }
+
+        let alternating = LinearRecurrence { p: -1i64, q: 2, a0: -5, a1: 3 };
+        for n in 0..20 {
+            assert_eq!(alternating.nth_mod(n, 7), alternating.nth(n).rem_euclid(7));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "modulus must be nonzero")]
+    fn zero_modulus() {
+        LinearRecurrence::<u64>::fibonacci().nth_mod(10, 0);
+    }
+
+    #[test]
+    fn pauli_matrices_multiply_like_multivectors() {
+        use crate::random::{Random, SplitMix64};
+
+        let mut rng = SplitMix64::new(40);
+        for _ in 0..200 {
+            let a = Frame::<i64>::random(&mut rng);
+            let b = Frame::<i64>::random(&mut rng);
+            let product = &PauliMatrix::from(&a) * &PauliMatrix::from(&b);
+            assert_eq!(product, PauliMatrix::from(&(&a * &b)));
+        }
+
+        let a = Frame::<f64>::random(&mut rng);
+        assert_eq!(Frame::from(&PauliMatrix::from(&a)), a);
+        let i = ComplexNumber { re: 0.0, im: 1.0 };
+        assert_eq!(i * i, ComplexNumber { re: -1.0, im: 0.0 });
+    }
+
+    #[test]
+    fn kronecker_products() {
+        let a: Matrix2<i32> = Matrix22 {
+            ul: Just(1), ur: Just(2),
+            ll: Just(3), lr: Just(4),
+        };
+        let b: Matrix2<i32> = Matrix22 {
+            ul: Just(0), ur: Just(5),
+            ll: Just(6), lr: Just(7),
+        };
+        let ab = a.kronecker(&b);
+        assert_eq!((ab.m11, ab.m12, ab.m13, ab.m14), (Just(0), Just(5), Just(0), Just(10)));
+        assert_eq!((ab.m41, ab.m42, ab.m43, ab.m44), (Just(18), Just(21), Just(24), Just(28)));
+        assert_eq!(ab.trace(), Just(a.trace().0 * b.trace().0));
+
+        // The mixed-product property, (A ⊗ B)(C ⊗ D) = AC ⊗ BD.
+        let (c, d) = (b.transpose(), a.transpose());
+        assert_eq!(&ab * &c.kronecker(&d), (&a * &c).kronecker(&(&b * &d)));
+    }
+
+    #[test]
+    fn identity_padded_operators_stay_sparse() {
+        let identity = Matrix22 {
+            ul: Just(1), ur: Nil(),
+            ll: Nil(), lr: Just(1),
+        };
+        let not = Matrix22 {
+            ul: Nil(), ur: Just(1),
+            ll: Just(1), lr: Nil(),
+        };
+        // NOT on the second qubit only, which is block diagonal.
+        type NotOnSecond = Matrix44<
+            Nil, Just<i32>, Nil, Nil,
+            Just<i32>, Nil, Nil, Nil,
+            Nil, Nil, Nil, Just<i32>,
+            Nil, Nil, Just<i32>, Nil,
+        >;
+        let operator: NotOnSecond = identity.kronecker(&not);
+        assert_eq!(operator.trace(), Nil());
+        assert_eq!(identity.kronecker(&identity).trace(), Just(4));
+
+        let affine = Matrix33 {
+            m11: Just(2), m12: Just(1), m13: Just(3),
+            m21: Just(1), m22: Just(5), m23: Just(4),
+            m31: Nil(), m32: Nil(), m33: Just(1),
+        };
+        assert_eq!(affine.trace(), Just(8));
+    }
+
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn symmetric_eigen_2x2() {
+        let m = Matrix22 {
+            ul: Just(2.0), ur: Just(1.0),
+            ll: Just(1.0), lr: Just(2.0),
+        };
+        let eigen = m.symmetric_eigen(1e-12).unwrap();
+        assert!(close(eigen.values[0], 1.0) && close(eigen.values[1], 3.0));
+        for (&value, vector) in eigen.values.iter().zip(&eigen.vectors) {
+            let image = &m * vector;
+            assert!(close(image.x.0, value * vector.x.0) && close(image.y.0, value * vector.y.0));
+            assert!(close((&vector.transpose() * vector).0, 1.0));
+        }
+
+        let diagonal = Matrix22 {
+            ul: Just(5.0), ur: Just(0.0),
+            ll: Just(0.0), lr: Just(-1.0),
+        };
+        let eigen = diagonal.symmetric_eigen(0.0).unwrap();
+        assert_eq!(eigen.values, [-1.0, 5.0]);
+        assert_eq!(eigen.vectors[0], Vector2 { x: Just(0.0), y: Just(1.0) });
+
+        let skewed = Matrix22 {
+            ul: Just(1.0), ur: Just(2.0),
+            ll: Just(0.0), lr: Just(1.0),
+        };
+        assert_eq!(skewed.symmetric_eigen(1e-9), None);
+    }
+
+    #[test]
+    fn symmetric_eigen_3x3() {
+        // An inertia tensor with principal axes along a rotated frame.
+        let m = Matrix33 {
+            m11: Just(4.0), m12: Just(1.0), m13: Just(-2.0),
+            m21: Just(1.0), m22: Just(3.0), m23: Just(0.5),
+            m31: Just(-2.0), m32: Just(0.5), m33: Just(6.0),
+        };
+        let eigen = m.symmetric_eigen(1e-12).unwrap();
+        assert!(eigen.values[0] <= eigen.values[1] && eigen.values[1] <= eigen.values[2]);
+        assert!(close(eigen.values.iter().sum(), m.trace().0));
+
+        let rows = m.rows();
+        let axes = [(1.0f64, 0.0, 0.0), (0.0, 1.0, 0.0), (0.0, 0.0, 1.0)];
+        for ((&value, vector), &(x, y, z)) in eigen.values.iter().zip(&eigen.vectors).zip(&axes) {
+            let v = [vector.e1.0, vector.e2.0, vector.e3.0];
+            for (row, &vi) in rows.iter().zip(&v) {
+                let image: f64 = row.iter().zip(&v).map(|(a, b)| a * b).sum();
+                assert!(close(image, value * vi));
+            }
+            // The rotor takes the basis vectors to the eigenvectors.
+            let r = &eigen.rotation;
+            let rotated = &(r * &Vec3::new(x, y, z)) * &r.clone().reverse();
+            assert!(close(rotated.e1.0, v[0]) && close(rotated.e2.0, v[1]) && close(rotated.e3.0, v[2]));
+        }
+        assert!(close((&eigen.rotation * &eigen.rotation.clone().reverse()).e.0, 1.0));
+    }
+
+    #[test]
+    fn symmetric_eigen_3x3_degenerate() {
+        let m = Matrix33 {
+            m11: Just(2.0), m12: Just(0.0), m13: Just(0.0),
+            m21: Just(0.0), m22: Just(-1.0), m23: Just(0.0),
+            m31: Just(0.0), m32: Just(0.0), m33: Just(2.0),
+        };
+        let eigen = m.symmetric_eigen(0.0).unwrap();
+        assert_eq!(eigen.values, [-1.0, 2.0, 2.0]);
+        let handedness = eigen.vectors[0].wedge(&eigen.vectors[1]).wedge(&eigen.vectors[2]).e123.0;
+        assert!(close(handedness, 1.0));
+
+        let mut asymmetric = m.clone();
+        asymmetric.m12 = Just(1.0);
+        assert_eq!(asymmetric.symmetric_eigen(1e-9), None);
+    }
+
+    #[test]
+    fn lu_solve_and_det() {
+        let m: Matrix2<f64> = Matrix22 {
+            ul: Just(0.0), ur: Just(2.0),
+            ll: Just(3.0), lr: Just(4.0),
+        };
+        assert_eq!(m.lu().unwrap().det(), m.det().0);
+        assert_eq!(m.solve(&[2.0, 7.0]), Ok([1.0, 1.0]));
+
+        // Needs a row swap at the first step, since the top-left entry is 0.
+        let m = Matrix33 {
+            m11: Just(0.0), m12: Just(1.0), m13: Just(2.0),
+            m21: Just(1.0), m22: Just(0.0), m23: Just(3.0),
+            m31: Just(4.0), m32: Just(-3.0), m33: Just(8.0),
+        };
+        assert!(close(m.det(), -2.0));
+        let x = m.solve(&[8.0, 10.0, 22.0]).unwrap();
+        assert!(close(x[0], 1.0) && close(x[1], 2.0) && close(x[2], 3.0));
+
+        let m: Matrix4<f64> = Matrix44 {
+            m11: Just(2.0), m12: Just(1.0), m13: Just(0.0), m14: Just(0.0),
+            m21: Just(1.0), m22: Just(3.0), m23: Just(1.0), m24: Just(0.0),
+            m31: Just(0.0), m32: Just(1.0), m33: Just(4.0), m34: Just(1.0),
+            m41: Just(0.0), m42: Just(0.0), m43: Just(1.0), m44: Just(5.0),
+        };
+        let lu = m.lu().unwrap();
+        let b = [4.0, 10.0, 18.0, 23.0];
+        let x = lu.solve(&b);
+        for (i, &expected) in [1.0, 2.0, 3.0, 4.0].iter().enumerate() {
+            assert!(close(x[i], expected));
+        }
+        assert!(close(m.det(), 2.0 * (3.0 * 19.0 - 5.0) - 19.0));
+
+        // Too close to singular to solve, but the determinant is still exact.
+        let m = Matrix33 {
+            m11: Just(1.0), m12: Just(0.0), m13: Just(0.0),
+            m21: Just(0.0), m22: Just(1e-16), m23: Just(0.0),
+            m31: Just(0.0), m32: Just(0.0), m33: Just(1.0),
+        };
+        assert_eq!(m.lu(), Err(SingularMatrix));
+        assert_eq!(m.det(), 1e-16);
+        let m = Matrix44 {
+            m11: Just(1e-20f64), m12: Just(0.0), m13: Just(0.0), m14: Just(0.0),
+            m21: Just(0.0), m22: Just(3.0), m23: Just(1e-20), m24: Just(0.0),
+            m31: Just(0.0), m32: Just(0.0), m33: Just(-1.0), m34: Just(0.0),
+            m41: Just(0.0), m42: Just(0.0), m43: Just(0.0), m44: Just(2.0),
+        };
+        assert!((m.det() / -6e-20 - 1.0).abs() < 1e-12);
+        let small: Matrix2<f64> = Matrix22 {
+            ul: Just(1e-20), ur: Just(2.0),
+            ll: Just(0.0), lr: Just(3.0),
+        };
+        let block = Matrix33 {
+            m11: Just(1e-20), m12: Just(2.0), m13: Just(0.0),
+            m21: Just(0.0), m22: Just(3.0), m23: Just(0.0),
+            m31: Just(0.0), m32: Just(0.0), m33: Just(1.0),
+        };
+        assert_eq!(block.det(), small.det().0);
+    }
+
+    #[test]
+    fn singular_systems_are_errors() {
+        let m = Matrix33 {
+            m11: Just(1.0), m12: Just(2.0), m13: Just(3.0),
+            m21: Just(4.0), m22: Just(5.0), m23: Just(6.0),
+            m31: Just(5.0), m32: Just(7.0), m33: Just(9.0),
+        };
+        assert_eq!(m.solve(&[1.0, 2.0, 3.0]), Err(SingularMatrix));
+        // Rounding leaves a last pivot of a few ulps rather than zero.
+        assert!(close(m.det(), 0.0));
+        let zero = Matrix22 {
+            ul: Just(0.0), ur: Just(0.0),
+            ll: Just(0.0), lr: Just(0.0),
+        };
+        assert_eq!(zero.lu(), Err(SingularMatrix));
+        assert_eq!(SingularMatrix.to_string(), "matrix is singular");
+    }
+}
@@ -0,0 +1,202 @@
+use std::ops::Mul;
+
+use super::{Just, Multivector3, Nil};
+
+/// A blade slot that either holds a coefficient of type `T` or is statically
+/// absent.
+pub trait Component<T>: Copy {
+    const PRESENT: bool;
+    fn coefficient(self) -> Option<T>;
+}
+impl<T: Copy> Component<T> for Just<T> {
+    const PRESENT: bool = true;
+    fn coefficient(self) -> Option<T> {
+        Some(self.0)
+    }
+}
+impl<T> Component<T> for Nil {
+    const PRESENT: bool = false;
+    fn coefficient(self) -> Option<T> {
+        None
+    }
+}
+
+/// Merges two blade slots into one that is `Just` if either is, provided
+/// they agree on the coefficient type.
+pub trait Join<B> {
+    type Output;
+}
+impl<T> Join<Just<T>> for Just<T> {
+    type Output = Just<T>;
+}
+impl<T> Join<Nil> for Just<T> {
+    type Output = Just<T>;
+}
+impl<T> Join<Just<T>> for Nil {
+    type Output = Just<T>;
+}
+impl Join<Nil> for Nil {
+    type Output = Nil;
+}
+pub type Joined<A, B> = <A as Join<B>>::Output;
+
+/// The coefficient type of a joined blade slot. A multivector with no blades
+/// at all has `Nil` as its scalar type.
+pub trait Coefficient {
+    type Scalar;
+}
+impl<T> Coefficient for Just<T> {
+    type Scalar = T;
+}
+impl Coefficient for Nil {
+    type Scalar = Nil;
+}
+
+/// Joins a tuple of blade slots down to a single one.
+pub trait JoinAll {
+    type Output;
+}
+impl<A: Join<B>, B> JoinAll for (A, B) {
+    type Output = Joined<A, B>;
+}
+impl<A, B, C, D> JoinAll for (A, B, C, D)
+where
+    (A, B): JoinAll,
+    (C, D): JoinAll,
+    <(A, B) as JoinAll>::Output: Join<<(C, D) as JoinAll>::Output>,
+{
+    type Output = Joined<<(A, B) as JoinAll>::Output, <(C, D) as JoinAll>::Output>;
+}
+impl<A, B, C, D, E, F, G, H> JoinAll for (A, B, C, D, E, F, G, H)
+where
+    (A, B, C, D): JoinAll,
+    (E, F, G, H): JoinAll,
+    <(A, B, C, D) as JoinAll>::Output: Join<<(E, F, G, H) as JoinAll>::Output>,
+{
+    type Output = Joined<<(A, B, C, D) as JoinAll>::Output, <(E, F, G, H) as JoinAll>::Output>;
+}
+
+/// Any multivector-like value, sparse or dense, with the bounds needed to
+/// inspect and multiply it already taken care of. Blades are numbered in the
+/// order of the fields of the implementing type.
+pub trait Multivector: Sized {
+    type Scalar;
+
+    /// The number of basis blades in the algebra.
+    const BLADES: usize;
+    /// Bit `i` is set when blade `i` is stored.
+    const MASK: u32;
+
+    /// The grade of basis blade `blade`, or `None` if there is no such blade.
+    fn grade(blade: usize) -> Option<usize>;
+    /// The coefficient of basis blade `blade`, or `None` if it is not stored.
+    fn component(&self, blade: usize) -> Option<Self::Scalar>;
+
+    /// Bit `k` is set when some stored blade has grade `k`.
+    fn grades() -> u32 {
+        (0..Self::BLADES)
+            .filter(|blade| Self::MASK & (1 << blade) != 0)
+            .filter_map(Self::grade)
+            .fold(0, |grades, grade| grades | (1 << grade))
+    }
+}
+
+/// Multivectors that can be multiplied by `Rhs`, with the result type named.
+/// This is kept apart from `Multivector` because asking for the product to be
+/// a multivector again would make the bounds recurse forever.
+pub trait GeometricProduct<Rhs = Self>: Multivector {
+    type Output: Multivector;
+    fn product(&self, rhs: &Rhs) -> Self::Output;
+}
+
+impl<S, E, E1, E2, E3, E12, E31, E23, E123> Multivector for Multivector3<E, E1, E2, E3, E12, E31, E23, E123>
+where
+    E: Component<S>, E1: Component<S>, E2: Component<S>, E3: Component<S>,
+    E12: Component<S>, E31: Component<S>, E23: Component<S>, E123: Component<S>,
+    (E, E1, E2, E3, E12, E31, E23, E123): JoinAll,
+    <(E, E1, E2, E3, E12, E31, E23, E123) as JoinAll>::Output: Coefficient<Scalar = S>,
+{
+    type Scalar = S;
+
+    const BLADES: usize = 8;
+    const MASK: u32 = E::PRESENT as u32
+        | (E1::PRESENT as u32) << 1
+        | (E2::PRESENT as u32) << 2
+        | (E3::PRESENT as u32) << 3
+        | (E12::PRESENT as u32) << 4
+        | (E31::PRESENT as u32) << 5
+        | (E23::PRESENT as u32) << 6
+        | (E123::PRESENT as u32) << 7;
+
+    fn grade(blade: usize) -> Option<usize> {
+        [0, 1, 1, 1, 2, 2, 2, 3].get(blade).copied()
+    }
+    fn component(&self, blade: usize) -> Option<S> {
+        match blade {
+            0 => self.e.coefficient(),
+            1 => self.e1.coefficient(),
+            2 => self.e2.coefficient(),
+            3 => self.e3.coefficient(),
+            4 => self.e12.coefficient(),
+            5 => self.e31.coefficient(),
+            6 => self.e23.coefficient(),
+            7 => self.e123.coefficient(),
+            _ => None,
+        }
+    }
+}
+
+impl<P, Rhs, E, E1, E2, E3, E12, E31, E23, E123> GeometricProduct<Rhs>
+    for Multivector3<E, E1, E2, E3, E12, E31, E23, E123>
+where
+    Self: Multivector,
+    for<'a> &'a Self: Mul<&'a Rhs, Output = P>,
+    P: Multivector,
+{
+    type Output = P;
+    fn product(&self, rhs: &Rhs) -> P {
+        self * rhs
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Rotor, Vec3, XYZ};
+
+    // The whole point: no `Se*` bounds in sight.
+    fn norm_squared<M: Multivector<Scalar = f64>>(m: &M) -> f64 {
+        (0..M::BLADES).filter_map(|blade| m.component(blade)).map(|c| c * c).sum()
+    }
+    fn square_scalar<M>(m: &M) -> Option<f64>
+    where
+        M: GeometricProduct,
+        M::Output: Multivector<Scalar = f64>,
+    {
+        m.product(m).component(0)
+    }
+
+    #[test]
+    fn blade_masks_and_grades() {
+        assert_eq!(<Vec3<f64> as Multivector>::MASK, 0b0000_1110);
+        assert_eq!(<Rotor<f64> as Multivector>::MASK, 0b0111_0001);
+        assert_eq!(<Rotor<f64> as Multivector>::grades(), 0b101);
+        assert_eq!(<XYZ<i32> as Multivector>::grades(), 0b1000);
+        assert_eq!(<Vec3<f64> as Multivector>::grade(7), Some(3));
+        assert_eq!(<Vec3<f64> as Multivector>::grade(8), None);
+    }
+
+    #[test]
+    fn generic_access() {
+        let v: Vec3<f64> = Multivector3 {
+            e: Nil(),
+            e1: Just(1.0), e2: Just(2.0), e3: Just(2.0),
+            e12: Nil(), e31: Nil(), e23: Nil(),
+            e123: Nil(),
+        };
+        assert_eq!(norm_squared(&v), 9.0);
+        assert_eq!(v.component(0), None);
+        assert_eq!(square_scalar(&v), Some(9.0));
+        assert_eq!(norm_squared(&v.product(&v)), 81.0);
+    }
+}
@@ -0,0 +1,259 @@
+use super::real::Real;
+use super::{Bivec, Just, Multivector3, Nil, Rotor, Se, Vec3};
+
+/// The state of a rigid body. The orientation rotor takes body coordinates to
+/// world coordinates as `R v ~R`, and the angular velocity is kept in the body
+/// frame, where the inertia tensor is constant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RigidBodyState<T> {
+    pub position: Vec3<T>,
+    pub orientation: Rotor<T>,
+    pub linear_velocity: Vec3<T>,
+    pub angular_velocity: Bivec<T>,
+}
+
+/// A body-frame inertia tensor, as the linear map from angular velocity
+/// bivectors to angular momentum bivectors. It is stored as the images of the
+/// three basis bivectors, alongside the images under its inverse.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inertia<T> {
+    e23: Bivec<T>,
+    e31: Bivec<T>,
+    e12: Bivec<T>,
+    inverse: (Bivec<T>, Bivec<T>, Bivec<T>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RigidBody<T> {
+    pub mass: T,
+    pub inertia: Inertia<T>,
+}
+
+fn bivec<T>(e23: T, e31: T, e12: T) -> Bivec<T> {
+    Multivector3 {
+        e: Nil(),
+        e1: Nil(), e2: Nil(), e3: Nil(),
+        e12: Just(e12), e31: Just(e31), e23: Just(e23),
+        e123: Nil(),
+    }
+}
+
+fn bivector_part<T>(r: Rotor<T>) -> Bivec<T> {
+    Multivector3 {
+        e: Nil(),
+        e1: Nil(), e2: Nil(), e3: Nil(),
+        e12: r.e12, e31: r.e31, e23: r.e23,
+        e123: Nil(),
+    }
+}
+
+fn sandwich<T: Real>(r: &Rotor<T>, b: &Bivec<T>) -> Bivec<T> {
+    bivector_part(&(r * b) * &r.clone().reverse())
+}
+
+/// `exp(-b h / 2)`, the rotor that turns through `b` at unit rate for time `h`.
+fn exp_half<T: Real>(b: &Bivec<T>, h: T) -> Rotor<T> {
+    let two = T::one() + T::one();
+    let rate = (-(b * b).e.0).sqrt();
+    let identity = Multivector3 {
+        e: Just(T::one()),
+        e1: Nil(), e2: Nil(), e3: Nil(),
+        e12: Just(T::zero()), e31: Just(T::zero()), e23: Just(T::zero()),
+        e123: Nil(),
+    };
+    if rate == T::zero() {
+        return identity;
+    }
+    let angle = rate * h / two;
+    Se(Just(angle.cos())) * &identity + Se(Just(-angle.sin() / rate)) * b
+}
+
+impl<T: Real> Inertia<T> {
+    /// The inertia tensor of a body whose principal axes are the basis
+    /// vectors, with the given principal moments about e1, e2 and e3.
+    pub fn diagonal(i1: T, i2: T, i3: T) -> Option<Self> {
+        let zero = T::zero();
+        Inertia::new(bivec(i1, zero, zero), bivec(zero, i2, zero), bivec(zero, zero, i3))
+    }
+
+    /// Builds an inertia tensor from the momenta produced by unit angular
+    /// velocity in each basis plane. Returns `None` if the map is singular.
+    pub fn new(e23: Bivec<T>, e31: Bivec<T>, e12: Bivec<T>) -> Option<Self> {
+        let column = |b: &Bivec<T>| [b.e23.0, b.e31.0, b.e12.0];
+        let (a, b, c) = (column(&e23), column(&e31), column(&e12));
+        let cross = |u: [T; 3], v: [T; 3]| [
+            u[1] * v[2] - u[2] * v[1],
+            u[2] * v[0] - u[0] * v[2],
+            u[0] * v[1] - u[1] * v[0],
+        ];
+        let (r1, r2, r3) = (cross(b, c), cross(c, a), cross(a, b));
+        let det = a[0] * r1[0] + a[1] * r1[1] + a[2] * r1[2];
+        if det == T::zero() {
+            return None;
+        }
+        let inverse_column = |j: usize| bivec(r1[j] / det, r2[j] / det, r3[j] / det);
+        let inverse = (inverse_column(0), inverse_column(1), inverse_column(2));
+        Some(Inertia { e23, e31, e12, inverse })
+    }
+
+    /// The angular momentum of a body spinning at `omega`.
+    pub fn apply(&self, omega: &Bivec<T>) -> Bivec<T> {
+        Se(omega.e23) * &self.e23 + Se(omega.e31) * &self.e31 + Se(omega.e12) * &self.e12
+    }
+
+    /// The angular velocity of a body with angular momentum `momentum`.
+    pub fn inverse_apply(&self, momentum: &Bivec<T>) -> Bivec<T> {
+        let (e23, e31, e12) = &self.inverse;
+        Se(momentum.e23) * e23 + Se(momentum.e31) * e31 + Se(momentum.e12) * e12
+    }
+}
+
+struct Derivative<T> {
+    velocity: Vec3<T>,
+    spin: Rotor<T>,
+    acceleration: Vec3<T>,
+    angular_acceleration: Bivec<T>,
+}
+
+fn advance<T: Real>(state: &RigidBodyState<T>, rate: &Derivative<T>, h: T) -> RigidBodyState<T> {
+    RigidBodyState {
+        position: state.position.clone() + Se(Just(h)) * &rate.velocity,
+        orientation: state.orientation.clone() + Se(Just(h)) * &rate.spin,
+        linear_velocity: state.linear_velocity.clone() + Se(Just(h)) * &rate.acceleration,
+        angular_velocity: state.angular_velocity.clone() + Se(Just(h)) * &rate.angular_acceleration,
+    }
+}
+
+impl<T: Real> RigidBody<T> {
+    /// Total kinetic energy, translational plus rotational.
+    pub fn kinetic_energy(&self, state: &RigidBodyState<T>) -> T {
+        let two = T::one() + T::one();
+        let momentum = self.inertia.apply(&state.angular_velocity);
+        let translational = self.mass * (&state.linear_velocity * &state.linear_velocity).e.0;
+        let rotational = -(&state.angular_velocity * &momentum).e.0;
+        (translational + rotational) / two
+    }
+
+    /// Angular momentum about the centre of mass, in the world frame.
+    pub fn angular_momentum(&self, state: &RigidBodyState<T>) -> Bivec<T> {
+        sandwich(&state.orientation, &self.inertia.apply(&state.angular_velocity))
+    }
+
+    // `forces` gives the world-frame force and torque acting on the body. In
+    // the body frame, Euler's equations read I(dΩ/dt) = <Ω I(Ω)>₂ + τ.
+    fn derivative<F>(&self, state: &RigidBodyState<T>, forces: &F) -> Derivative<T>
+    where
+        F: Fn(&RigidBodyState<T>) -> (Vec3<T>, Bivec<T>),
+    {
+        let two = T::one() + T::one();
+        let (force, torque) = forces(state);
+        let body_torque = sandwich(&state.orientation.clone().reverse(), &torque);
+        let momentum = self.inertia.apply(&state.angular_velocity);
+        let gyroscopic = bivector_part(&state.angular_velocity * &momentum);
+        Derivative {
+            velocity: state.linear_velocity.clone(),
+            spin: Se(Just(-T::one() / two)) * &(&state.orientation * &state.angular_velocity),
+            acceleration: Se(Just(T::one() / self.mass)) * &force,
+            angular_acceleration: self.inertia.inverse_apply(&(gyroscopic + body_torque)),
+        }
+    }
+
+    /// Advances the state by `h` with semi-implicit Euler: velocities are
+    /// updated first, and the new velocities are used to move the body. The
+    /// orientation is advanced with the exact rotor for the new angular
+    /// velocity, then re-normalized.
+    pub fn semi_implicit_euler<F>(&self, state: &RigidBodyState<T>, h: T, forces: F) -> RigidBodyState<T>
+    where
+        F: Fn(&RigidBodyState<T>) -> (Vec3<T>, Bivec<T>),
+    {
+        let rate = self.derivative(state, &forces);
+        let linear_velocity = state.linear_velocity.clone() + Se(Just(h)) * &rate.acceleration;
+        let angular_velocity = state.angular_velocity.clone() + Se(Just(h)) * &rate.angular_acceleration;
+        RigidBodyState {
+            position: state.position.clone() + Se(Just(h)) * &linear_velocity,
+            orientation: (&state.orientation * &exp_half(&angular_velocity, h)).normalized(),
+            linear_velocity,
+            angular_velocity,
+        }
+    }
+
+    /// Advances the state by `h` with the classical fourth order Runge-Kutta
+    /// method, then re-normalizes the orientation.
+    pub fn rk4<F>(&self, state: &RigidBodyState<T>, h: T, forces: F) -> RigidBodyState<T>
+    where
+        F: Fn(&RigidBodyState<T>) -> (Vec3<T>, Bivec<T>),
+    {
+        let two = T::one() + T::one();
+        let six = two + two + two;
+        let k1 = self.derivative(state, &forces);
+        let k2 = self.derivative(&advance(state, &k1, h / two), &forces);
+        let k3 = self.derivative(&advance(state, &k2, h / two), &forces);
+        let k4 = self.derivative(&advance(state, &k3, h), &forces);
+        let next = advance(state, &k1, h / six);
+        let next = advance(&next, &k2, h / (six / two));
+        let next = advance(&next, &k3, h / (six / two));
+        let mut next = advance(&next, &k4, h / six);
+        next.orientation = next.orientation.normalized();
+        next
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn torque_free(_state: &RigidBodyState<f64>) -> (Vec3<f64>, Bivec<f64>) {
+        (Vec3::new(0.0, 0.0, 0.0), bivec(0.0, 0.0, 0.0))
+    }
+    // Spinning mostly about the intermediate axis, so the body tumbles.
+    fn tumbling() -> (RigidBody<f64>, RigidBodyState<f64>) {
+        let body = RigidBody { mass: 2.0, inertia: Inertia::diagonal(1.0, 2.0, 3.0).unwrap() };
+        let state = RigidBodyState {
+            position: Vec3::new(0.0, 0.0, 0.0),
+            orientation: exp_half(&bivec(0.0, 0.0, 0.0), 0.0),
+            linear_velocity: Vec3::new(1.0, -0.5, 0.25),
+            angular_velocity: bivec(0.05, 2.0, 0.05),
+        };
+        (body, state)
+    }
+    fn distance(a: &Bivec<f64>, b: &Bivec<f64>) -> f64 {
+        let d: Bivec<f64> = a.clone() + Se(Just(-1.0)) * b;
+        (-(&d * &d).e.0).sqrt()
+    }
+
+    #[test]
+    fn inertia_inverse() {
+        let inertia = Inertia::new(bivec(2.0, 0.5, 0.0), bivec(0.5, 3.0, 0.1), bivec(0.0, 0.1, 4.0)).unwrap();
+        let omega = bivec(0.3, -1.2, 0.7);
+        assert!(distance(&inertia.inverse_apply(&inertia.apply(&omega)), &omega) < 1e-12);
+        assert_eq!(Inertia::diagonal(1.0, 0.0, 1.0), None);
+    }
+
+    #[test]
+    fn rk4_conserves_torque_free_invariants() {
+        let (body, mut state) = tumbling();
+        let energy = body.kinetic_energy(&state);
+        let momentum = body.angular_momentum(&state);
+        for _ in 0..10_000 {
+            state = body.rk4(&state, 1e-3, torque_free);
+        }
+        assert!((body.kinetic_energy(&state) - energy).abs() < 1e-9 * energy);
+        assert!(distance(&body.angular_momentum(&state), &momentum) < 1e-8);
+        let offset: Vec3<f64> = state.position.clone() + Se(Just(-1.0)) * &Vec3::new(10.0, -5.0, 2.5);
+        assert!((&offset * &offset).e.0.sqrt() < 1e-9);
+        assert!(((&state.orientation * &state.orientation.clone().reverse()).e.0 - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn semi_implicit_euler_stays_close() {
+        let (body, mut state) = tumbling();
+        let energy = body.kinetic_energy(&state);
+        let momentum = body.angular_momentum(&state);
+        for _ in 0..10_000 {
+            state = body.semi_implicit_euler(&state, 1e-3, torque_free);
+        }
+        assert!((body.kinetic_energy(&state) - energy).abs() < 1e-2 * energy);
+        assert!(distance(&body.angular_momentum(&state), &momentum) < 1e-1);
+        assert!(((&state.orientation * &state.orientation.clone().reverse()).e.0 - 1.0).abs() < 1e-12);
+    }
+}
@@ -0,0 +1,194 @@
+use std::f64::consts::PI;
+
+use super::real::Real;
+use super::{Bivec, Just, Multivector3, Nil, Rotor, Vec3};
+
+/// A source of random bits. Implement this for your own generator to drive
+/// the samplers below with it.
+pub trait Rng {
+    fn next_u64(&mut self) -> u64;
+
+    /// A uniform sample from `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+/// The SplitMix64 generator. Small and fast, and good enough for tests and
+/// Monte Carlo, but not for anything that needs to be unpredictable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SplitMix64 {
+    state: u64,
+}
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        SplitMix64 { state: seed }
+    }
+}
+impl Rng for SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+/// Types that can be sampled at random. Floats are uniform in `[-1, 1)`.
+/// Integers are uniform in `[-1024, 1024)`. Each blade of a product of two
+/// full multivectors is a sum of eight terms, so an `i32` holds a single
+/// product (under 2^23) but not a product of three (up to about 2^36). An
+/// `i64` holds a product of up to five. `Nil` blades stay `Nil`, so any
+/// sparse multivector alias only has its `Just` blades filled in.
+pub trait Random {
+    fn random<R: Rng>(rng: &mut R) -> Self;
+}
+impl Random for f64 {
+    fn random<R: Rng>(rng: &mut R) -> Self {
+        rng.next_f64() * 2.0 - 1.0
+    }
+}
+impl Random for f32 {
+    fn random<R: Rng>(rng: &mut R) -> Self {
+        f64::random(rng) as f32
+    }
+}
+impl Random for i32 {
+    fn random<R: Rng>(rng: &mut R) -> Self {
+        (rng.next_u64() >> 53) as i32 - 1024
+    }
+}
+impl Random for i64 {
+    fn random<R: Rng>(rng: &mut R) -> Self {
+        (rng.next_u64() >> 53) as i64 - 1024
+    }
+}
+impl<T: Random> Random for Just<T> {
+    fn random<R: Rng>(rng: &mut R) -> Self {
+        Just(T::random(rng))
+    }
+}
+impl Random for Nil {
+    fn random<R: Rng>(_rng: &mut R) -> Self {
+        Nil()
+    }
+}
+impl<E: Random, E1: Random, E2: Random, E3: Random, E12: Random, E31: Random, E23: Random, E123: Random>
+    Random for Multivector3<E, E1, E2, E3, E12, E31, E23, E123>
+{
+    fn random<R: Rng>(rng: &mut R) -> Self {
+        Multivector3 {
+            e: E::random(rng),
+            e1: E1::random(rng),
+            e2: E2::random(rng),
+            e3: E3::random(rng),
+            e12: E12::random(rng),
+            e31: E31::random(rng),
+            e23: E23::random(rng),
+            e123: E123::random(rng),
+        }
+    }
+}
+
+/// A vector drawn uniformly from the unit sphere.
+pub fn unit_vector<T: Real, R: Rng>(rng: &mut R) -> Vec3<T> {
+    let z = rng.next_f64() * 2.0 - 1.0;
+    let angle = rng.next_f64() * 2.0 * PI;
+    let r = (1.0 - z * z).sqrt();
+    Multivector3 {
+        e: Nil(),
+        e1: Just(T::from_f64(r * angle.cos())),
+        e2: Just(T::from_f64(r * angle.sin())),
+        e3: Just(T::from_f64(z)),
+        e12: Nil(), e31: Nil(), e23: Nil(),
+        e123: Nil(),
+    }
+}
+
+/// A unit rotor drawn uniformly from the rotation group, using Shoemake's
+/// method for uniform unit quaternions.
+pub fn unit_rotor<T: Real, R: Rng>(rng: &mut R) -> Rotor<T> {
+    let u = rng.next_f64();
+    let (a, b) = (rng.next_f64() * 2.0 * PI, rng.next_f64() * 2.0 * PI);
+    let (r1, r2) = ((1.0 - u).sqrt(), u.sqrt());
+    Multivector3 {
+        e: Just(T::from_f64(r2 * b.cos())),
+        e1: Nil(), e2: Nil(), e3: Nil(),
+        e12: Just(T::from_f64(r2 * b.sin())),
+        e31: Just(T::from_f64(r1 * a.cos())),
+        e23: Just(T::from_f64(r1 * a.sin())),
+        e123: Nil(),
+    }
+}
+
+/// A bivector with the given magnitude, in a plane drawn uniformly at random.
+pub fn bivector<T: Real, R: Rng>(rng: &mut R, magnitude: T) -> Bivec<T> {
+    let normal = unit_vector::<T, R>(rng);
+    Multivector3 {
+        e: Nil(),
+        e1: Nil(), e2: Nil(), e3: Nil(),
+        e12: Just(magnitude * normal.e3.0),
+        e31: Just(magnitude * normal.e2.0),
+        e23: Just(magnitude * normal.e1.0),
+        e123: Nil(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Frame;
+
+    #[test]
+    fn seeded_generators_repeat() {
+        let mut a = SplitMix64::new(42);
+        let mut b = SplitMix64::new(42);
+        let x: Frame<f64> = Random::random(&mut a);
+        let y: Frame<f64> = Random::random(&mut b);
+        assert_eq!(x, y);
+        assert_ne!(SplitMix64::new(1).next_u64(), SplitMix64::new(2).next_u64());
+    }
+
+    #[test]
+    fn samples_have_requested_norms() {
+        let mut rng = SplitMix64::new(7);
+        for _ in 0..1000 {
+            let v: Vec3<f64> = unit_vector(&mut rng);
+            assert!(((&v * &v).e.0 - 1.0).abs() < 1e-12);
+            let r: Rotor<f64> = unit_rotor(&mut rng);
+            assert!(((&r * &r.clone().reverse()).e.0 - 1.0).abs() < 1e-12);
+            let b: Bivec<f64> = bivector(&mut rng, 2.5);
+            assert!(((&b * &b).e.0 + 6.25).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn rotors_are_uniform() {
+        let mut rng = SplitMix64::new(3);
+        let x: Vec3<f64> = unit_vector(&mut rng);
+        let n = 20_000;
+        let mut mean = (0.0, 0.0, 0.0);
+        for _ in 0..n {
+            let r: Rotor<f64> = unit_rotor(&mut rng);
+            let y = &(&r * &x) * &r.clone().reverse();
+            mean = (mean.0 + y.e1.0, mean.1 + y.e2.0, mean.2 + y.e3.0);
+        }
+        let n = f64::from(n);
+        assert!((mean.0 / n).abs() < 0.02);
+        assert!((mean.1 / n).abs() < 0.02);
+        assert!((mean.2 / n).abs() < 0.02);
+    }
+
+    #[test]
+    fn product_is_associative_and_distributive() {
+        let mut rng = SplitMix64::new(2019);
+        for _ in 0..1000 {
+            let a: Frame<i64> = Random::random(&mut rng);
+            let b: Frame<i64> = Random::random(&mut rng);
+            let c: Frame<i64> = Random::random(&mut rng);
+            assert_eq!(&(&a * &b) * &c, &a * &(&b * &c));
+            assert_eq!(&a * &(b.clone() + c.clone()), (&a * &b) + (&a * &c));
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// Scalars with additive and multiplicative identities, so that square
+/// matrices of them have an identity and can be raised to powers.
+pub trait Ring: Copy + Add<Output = Self> + Mul<Output = Self> {
+    fn zero() -> Self;
+    fn one() -> Self;
+}
+macro_rules! impl_ring {
+    ($($t: ty: $zero: expr, $one: expr);*) => {
+        $(impl Ring for $t {
+            fn zero() -> Self {
+                $zero
+            }
+            fn one() -> Self {
+                $one
+            }
+        })*
+    };
+}
+impl_ring!(i32: 0, 1; i64: 0, 1; i128: 0, 1; u32: 0, 1; u64: 0, 1; u128: 0, 1; f32: 0.0, 1.0; f64: 0.0, 1.0);
+
+/// The scalar operations needed by the numeric routines built on top of the
+/// multivector types, beyond what `Ring` and `Neg` already give us.
+pub trait Real: Ring + Default + PartialOrd + Sub<Output = Self> + Div<Output = Self> + Neg<Output = Self> {
+    fn from_f64(value: f64) -> Self;
+    /// The gap between one and the next representable value above it.
+    fn epsilon() -> Self;
+    fn sqrt(self) -> Self;
+    fn sin(self) -> Self;
+    fn cos(self) -> Self;
+    fn sinh(self) -> Self;
+    fn cosh(self) -> Self;
+}
+
+macro_rules! impl_real {
+    ($t: ty) => {
+        impl Real for $t {
+            fn from_f64(value: f64) -> Self {
+                value as $t
+            }
+            fn epsilon() -> Self {
+                <$t>::EPSILON
+            }
+            fn sqrt(self) -> Self {
+                <$t>::sqrt(self)
+            }
+            fn sin(self) -> Self {
+                <$t>::sin(self)
+            }
+            fn cos(self) -> Self {
+                <$t>::cos(self)
+            }
+            fn sinh(self) -> Self {
+                <$t>::sinh(self)
+            }
+            fn cosh(self) -> Self {
+                <$t>::cosh(self)
+            }
+        }
+    };
+}
+impl_real!(f32);
+impl_real!(f64);
@@ -0,0 +1,415 @@
+use std::ops::Mul;
+
+use super::real::Real;
+use super::{Just, Multivector3, Nil, Rotor};
+
+/// A blade type that knows how to be stored column-wise. `Just<T>` blades get
+/// a `Vec<T>`, `Nil` blades get a `()` so they take no storage at all.
+pub trait Lane: Copy {
+    type Column: Default;
+    type View<'a>: Copy where Self: 'a;
+    type ViewMut<'a> where Self: 'a;
+    fn with_capacity(capacity: usize) -> Self::Column;
+    fn with_len(len: usize) -> Self::Column;
+    fn push(column: &mut Self::Column, value: Self);
+    fn view(column: &Self::Column, len: usize) -> Self::View<'_>;
+    fn view_mut(column: &mut Self::Column, len: usize) -> Self::ViewMut<'_>;
+    fn get(view: Self::View<'_>, index: usize) -> Self;
+    fn set(view: &mut Self::ViewMut<'_>, index: usize, value: Self);
+}
+impl<T: Copy + Default> Lane for Just<T> {
+    type Column = Vec<T>;
+    type View<'a> = &'a [T] where T: 'a;
+    type ViewMut<'a> = &'a mut [T] where T: 'a;
+    fn with_capacity(capacity: usize) -> Vec<T> {
+        Vec::with_capacity(capacity)
+    }
+    fn with_len(len: usize) -> Vec<T> {
+        vec![T::default(); len]
+    }
+    fn push(column: &mut Vec<T>, value: Self) {
+        column.push(value.0)
+    }
+    #[inline(always)]
+    fn view(column: &Vec<T>, len: usize) -> &[T] {
+        &column[..len]
+    }
+    #[inline(always)]
+    fn view_mut(column: &mut Vec<T>, len: usize) -> &mut [T] {
+        &mut column[..len]
+    }
+    #[inline(always)]
+    fn get(view: &[T], index: usize) -> Self {
+        Just(view[index])
+    }
+    #[inline(always)]
+    fn set(view: &mut &mut [T], index: usize, value: Self) {
+        view[index] = value.0
+    }
+}
+impl Lane for Nil {
+    type Column = ();
+    type View<'a> = ();
+    type ViewMut<'a> = ();
+    fn with_capacity(_capacity: usize) {}
+    fn with_len(_len: usize) {}
+    fn push(_column: &mut (), _value: Self) {}
+    #[inline(always)]
+    fn view(_column: &(), _len: usize) {}
+    #[inline(always)]
+    fn view_mut(_column: &mut (), _len: usize) {}
+    #[inline(always)]
+    fn get(_view: (), _index: usize) -> Self {
+        Nil()
+    }
+    #[inline(always)]
+    fn set(_view: &mut (), _index: usize, _value: Self) {}
+}
+
+macro_rules! soa_e {
+    () => {
+        MultivectorSoA<E, E1, E2, E3, E12, E31, E23, E123>
+    }
+}
+
+/// A batch of multivectors of the same sparse type, stored as one column per
+/// blade.
+pub struct MultivectorSoA<E: Lane, E1: Lane, E2: Lane, E3: Lane, E12: Lane, E31: Lane, E23: Lane, E123: Lane> {
+    len: usize,
+    e: E::Column,
+    e1: E1::Column,
+    e2: E2::Column,
+    e3: E3::Column,
+    e12: E12::Column,
+    e31: E31::Column,
+    e23: E23::Column,
+    e123: E123::Column,
+}
+pub type Vec3SoA<T> = MultivectorSoA<Nil, Just<T>, Just<T>, Just<T>, Nil, Nil, Nil, Nil>;
+pub type BivecSoA<T> = MultivectorSoA<Nil, Nil, Nil, Nil, Just<T>, Just<T>, Just<T>, Nil>;
+pub type RotorSoA<T> = MultivectorSoA<Just<T>, Nil, Nil, Nil, Just<T>, Just<T>, Just<T>, Nil>;
+pub type FrameSoA<T> = MultivectorSoA<Just<T>, Just<T>, Just<T>, Just<T>, Just<T>, Just<T>, Just<T>, Just<T>>;
+
+impl<E: Lane, E1: Lane, E2: Lane, E3: Lane, E12: Lane, E31: Lane, E23: Lane, E123: Lane> soa_e!() {
+    pub fn new() -> Self {
+        MultivectorSoA {
+            len: 0,
+            e: Default::default(),
+            e1: Default::default(),
+            e2: Default::default(),
+            e3: Default::default(),
+            e12: Default::default(),
+            e31: Default::default(),
+            e23: Default::default(),
+            e123: Default::default(),
+        }
+    }
+    pub fn with_capacity(capacity: usize) -> Self {
+        MultivectorSoA {
+            len: 0,
+            e: E::with_capacity(capacity),
+            e1: E1::with_capacity(capacity),
+            e2: E2::with_capacity(capacity),
+            e3: E3::with_capacity(capacity),
+            e12: E12::with_capacity(capacity),
+            e31: E31::with_capacity(capacity),
+            e23: E23::with_capacity(capacity),
+            e123: E123::with_capacity(capacity),
+        }
+    }
+    pub fn len(&self) -> usize {
+        self.len
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    pub fn push(&mut self, value: Multivector3<E, E1, E2, E3, E12, E31, E23, E123>) {
+        E::push(&mut self.e, value.e);
+        E1::push(&mut self.e1, value.e1);
+        E2::push(&mut self.e2, value.e2);
+        E3::push(&mut self.e3, value.e3);
+        E12::push(&mut self.e12, value.e12);
+        E31::push(&mut self.e31, value.e31);
+        E23::push(&mut self.e23, value.e23);
+        E123::push(&mut self.e123, value.e123);
+        self.len += 1;
+    }
+    #[inline(always)]
+    pub fn get(&self, index: usize) -> Multivector3<E, E1, E2, E3, E12, E31, E23, E123> {
+        assert!(index < self.len, "index {} out of bounds for batch of {}", index, self.len);
+        let len = self.len;
+        Multivector3 {
+            e: E::get(E::view(&self.e, len), index),
+            e1: E1::get(E1::view(&self.e1, len), index),
+            e2: E2::get(E2::view(&self.e2, len), index),
+            e3: E3::get(E3::view(&self.e3, len), index),
+            e12: E12::get(E12::view(&self.e12, len), index),
+            e31: E31::get(E31::view(&self.e31, len), index),
+            e23: E23::get(E23::view(&self.e23, len), index),
+            e123: E123::get(E123::view(&self.e123, len), index),
+        }
+    }
+}
+impl<E: Lane, E1: Lane, E2: Lane, E3: Lane, E12: Lane, E31: Lane, E23: Lane, E123: Lane> Default for soa_e!() {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<E: Lane, E1: Lane, E2: Lane, E3: Lane, E12: Lane, E31: Lane, E23: Lane, E123: Lane>
+    std::iter::FromIterator<Multivector3<E, E1, E2, E3, E12, E31, E23, E123>> for soa_e!()
+{
+    fn from_iter<I: IntoIterator<Item = Multivector3<E, E1, E2, E3, E12, E31, E23, E123>>>(iter: I) -> Self {
+        let mut soa = Self::new();
+        for value in iter {
+            soa.push(value);
+        }
+        soa
+    }
+}
+
+// Every column is sliced to exactly `len` before the loop so that the bounds
+// checks inside it can be proven away. After inlining, each iteration is a
+// straight-line product of a handful of slices into a handful of slices, with
+// the `Nil` blades gone entirely, which is the shape the auto-vectorizer wants.
+impl<'a,
+    E: Lane, E1: Lane, E2: Lane, E3: Lane, E12: Lane, E31: Lane, E23: Lane, E123: Lane,
+    F: Lane, F1: Lane, F2: Lane, F3: Lane, F12: Lane, F31: Lane, F23: Lane, F123: Lane,
+    G: Lane, G1: Lane, G2: Lane, G3: Lane, G12: Lane, G31: Lane, G23: Lane, G123: Lane>
+    Mul<&'a soa_e!()> for &'a MultivectorSoA<F, F1, F2, F3, F12, F31, F23, F123>
+where
+    for<'b> &'b Multivector3<F, F1, F2, F3, F12, F31, F23, F123>: Mul<
+        &'b Multivector3<E, E1, E2, E3, E12, E31, E23, E123>,
+        Output = Multivector3<G, G1, G2, G3, G12, G31, G23, G123>,
+    >,
+{
+    type Output = MultivectorSoA<G, G1, G2, G3, G12, G31, G23, G123>;
+    fn mul(self, rhs: &'a soa_e!()) -> Self::Output {
+        assert_eq!(self.len, rhs.len, "batch lengths differ");
+        let len = self.len;
+        let mut out = MultivectorSoA {
+            len,
+            e: G::with_len(len),
+            e1: G1::with_len(len),
+            e2: G2::with_len(len),
+            e3: G3::with_len(len),
+            e12: G12::with_len(len),
+            e31: G31::with_len(len),
+            e23: G23::with_len(len),
+            e123: G123::with_len(len),
+        };
+
+        let f = F::view(&self.e, len);
+        let f1 = F1::view(&self.e1, len);
+        let f2 = F2::view(&self.e2, len);
+        let f3 = F3::view(&self.e3, len);
+        let f12 = F12::view(&self.e12, len);
+        let f31 = F31::view(&self.e31, len);
+        let f23 = F23::view(&self.e23, len);
+        let f123 = F123::view(&self.e123, len);
+
+        let e = E::view(&rhs.e, len);
+        let e1 = E1::view(&rhs.e1, len);
+        let e2 = E2::view(&rhs.e2, len);
+        let e3 = E3::view(&rhs.e3, len);
+        let e12 = E12::view(&rhs.e12, len);
+        let e31 = E31::view(&rhs.e31, len);
+        let e23 = E23::view(&rhs.e23, len);
+        let e123 = E123::view(&rhs.e123, len);
+
+        // The output views have to be dropped before `out` can be returned.
+        {
+            let mut g = G::view_mut(&mut out.e, len);
+            let mut g1 = G1::view_mut(&mut out.e1, len);
+            let mut g2 = G2::view_mut(&mut out.e2, len);
+            let mut g3 = G3::view_mut(&mut out.e3, len);
+            let mut g12 = G12::view_mut(&mut out.e12, len);
+            let mut g31 = G31::view_mut(&mut out.e31, len);
+            let mut g23 = G23::view_mut(&mut out.e23, len);
+            let mut g123 = G123::view_mut(&mut out.e123, len);
+
+            for index in 0..len {
+                let lhs = Multivector3 {
+                    e: F::get(f, index),
+                    e1: F1::get(f1, index), e2: F2::get(f2, index), e3: F3::get(f3, index),
+                    e12: F12::get(f12, index), e31: F31::get(f31, index), e23: F23::get(f23, index),
+                    e123: F123::get(f123, index),
+                };
+                let rhs = Multivector3 {
+                    e: E::get(e, index),
+                    e1: E1::get(e1, index), e2: E2::get(e2, index), e3: E3::get(e3, index),
+                    e12: E12::get(e12, index), e31: E31::get(e31, index), e23: E23::get(e23, index),
+                    e123: E123::get(e123, index),
+                };
+                let product = &lhs * &rhs;
+                G::set(&mut g, index, product.e);
+                G1::set(&mut g1, index, product.e1);
+                G2::set(&mut g2, index, product.e2);
+                G3::set(&mut g3, index, product.e3);
+                G12::set(&mut g12, index, product.e12);
+                G31::set(&mut g31, index, product.e31);
+                G23::set(&mut g23, index, product.e23);
+                G123::set(&mut g123, index, product.e123);
+            }
+        }
+        out
+    }
+}
+
+// R v ~R for a unit rotor R = s + b12 e12 + b31 e31 + b23 e23, expanded so
+// that no intermediate multivector is built.
+#[inline(always)]
+fn rotate_components<T: Real>(s: T, b23: T, b31: T, b12: T, x: T, y: T, z: T) -> (T, T, T) {
+    let two = T::one() + T::one();
+    let tx = two * (b31 * z - b12 * y);
+    let ty = two * (b12 * x - b23 * z);
+    let tz = two * (b23 * y - b31 * x);
+    (
+        x - s * tx + (b31 * tz - b12 * ty),
+        y - s * ty + (b12 * tx - b23 * tz),
+        z - s * tz + (b23 * ty - b31 * tx),
+    )
+}
+
+impl<T: Real> Vec3SoA<T> {
+    /// Rotates every vector in the batch by the same unit rotor.
+    pub fn rotate(&mut self, rotor: &Rotor<T>) {
+        let (Just(s), Just(b12), Just(b31), Just(b23)) = (rotor.e, rotor.e12, rotor.e31, rotor.e23);
+        let xs = self.e1.iter_mut();
+        let ys = self.e2.iter_mut();
+        let zs = self.e3.iter_mut();
+        for ((x, y), z) in xs.zip(ys).zip(zs) {
+            let (rx, ry, rz) = rotate_components(s, b23, b31, b12, *x, *y, *z);
+            *x = rx;
+            *y = ry;
+            *z = rz;
+        }
+    }
+
+    /// Rotates each vector in the batch by the unit rotor at the same index.
+    pub fn rotate_each(&mut self, rotors: &RotorSoA<T>) {
+        assert_eq!(self.len, rotors.len, "batch lengths differ");
+        let xs = self.e1.iter_mut();
+        let ys = self.e2.iter_mut();
+        let zs = self.e3.iter_mut();
+        let rs = rotors.e.iter().zip(&rotors.e12).zip(&rotors.e31).zip(&rotors.e23);
+        for (((x, y), z), (((&s, &b12), &b31), &b23)) in xs.zip(ys).zip(zs).zip(rs) {
+            let (rx, ry, rz) = rotate_components(s, b23, b31, b12, *x, *y, *z);
+            *x = rx;
+            *y = ry;
+            *z = rz;
+        }
+    }
+}
+
+impl<T: Real> RotorSoA<T> {
+    /// Scales every rotor in the batch to unit norm.
+    pub fn normalize(&mut self) {
+        let ss = self.e.iter_mut();
+        let b12s = self.e12.iter_mut();
+        let b31s = self.e31.iter_mut();
+        let b23s = self.e23.iter_mut();
+        for (((s, b12), b31), b23) in ss.zip(b12s).zip(b31s).zip(b23s) {
+            let norm = (*s * *s + *b12 * *b12 + *b31 * *b31 + *b23 * *b23).sqrt();
+            let inv = T::one() / norm;
+            *s = *s * inv;
+            *b12 = *b12 * inv;
+            *b31 = *b31 * inv;
+            *b23 = *b23 * inv;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::Vec3;
+
+    fn rotor(i: usize) -> Rotor<f64> {
+        let t = i as f64;
+        Multivector3 {
+            e: Just((t * 0.37).cos() + 1.5),
+            e1: Nil(), e2: Nil(), e3: Nil(),
+            e12: Just((t * 0.11).sin()),
+            e31: Just((t * 0.23).cos()),
+            e23: Just((t * 0.05).sin() - 0.5),
+            e123: Nil(),
+        }
+    }
+    fn vector(i: usize) -> Vec3<f64> {
+        let t = i as f64;
+        Multivector3 {
+            e: Nil(),
+            e1: Just(t.sin()), e2: Just(t * 0.5), e3: Just(1.0 - t),
+            e12: Nil(), e31: Nil(), e23: Nil(),
+            e123: Nil(),
+        }
+    }
+    fn unit(r: Rotor<f64>) -> Rotor<f64> {
+        let mut soa: RotorSoA<f64> = std::iter::once(r).collect();
+        soa.normalize();
+        soa.get(0)
+    }
+    fn reverse(r: &Rotor<f64>) -> Rotor<f64> {
+        r.clone().reverse()
+    }
+    fn close(a: f64, b: f64) -> bool {
+        (a - b).abs() < 1e-9
+    }
+
+    #[test]
+    fn nil_blades_take_no_storage() {
+        // The length plus one column for each `Just` blade, and nothing else.
+        let column = size_of::<Vec<f64>>();
+        assert_eq!(size_of::<RotorSoA<f64>>(), size_of::<usize>() + 4 * column);
+        assert_eq!(size_of::<Vec3SoA<f64>>(), size_of::<usize>() + 3 * column);
+        assert_eq!(size_of::<FrameSoA<f64>>(), size_of::<usize>() + 8 * column);
+        let rotors: RotorSoA<f64> = (0..10).map(rotor).collect();
+        assert_eq!(rotors.len(), 10);
+        assert_eq!(rotors.e.len(), 10);
+        assert_eq!(rotors.e123, ());
+    }
+
+    #[test]
+    fn batch_product_matches_elementwise() {
+        let a: RotorSoA<f64> = (0..64).map(rotor).collect();
+        let b: Vec3SoA<f64> = (0..64).map(vector).collect();
+        let product = &a * &b;
+        for i in 0..64 {
+            assert_eq!(product.get(i), &rotor(i) * &vector(i));
+        }
+    }
+
+    #[test]
+    fn batch_rotate_matches_sandwich() {
+        let rotors: RotorSoA<f64> = (0..64).map(|i| unit(rotor(i))).collect();
+        let mut each: Vec3SoA<f64> = (0..64).map(vector).collect();
+        each.rotate_each(&rotors);
+        let mut same: Vec3SoA<f64> = (0..64).map(vector).collect();
+        same.rotate(&rotors.get(3));
+        for i in 0..64 {
+            let r = rotors.get(i);
+            let expected = &(&r * &vector(i)) * &reverse(&r);
+            let actual = each.get(i);
+            assert!(close(actual.e1.0, expected.e1.0));
+            assert!(close(actual.e2.0, expected.e2.0));
+            assert!(close(actual.e3.0, expected.e3.0));
+
+            let r = rotors.get(3);
+            let expected = &(&r * &vector(i)) * &reverse(&r);
+            let actual = same.get(i);
+            assert!(close(actual.e1.0, expected.e1.0));
+            assert!(close(actual.e2.0, expected.e2.0));
+            assert!(close(actual.e3.0, expected.e3.0));
+        }
+    }
+
+    #[test]
+    fn batch_normalize() {
+        let mut rotors: RotorSoA<f64> = (0..64).map(rotor).collect();
+        rotors.normalize();
+        for i in 0..64 {
+            let r = rotors.get(i);
+            assert!(close((&r * &reverse(&r)).e.0, 1.0));
+        }
+    }
+}
@@ -0,0 +1,532 @@
+use std::ops::{Add, Mul, Neg};
+
+use super::real::Real;
+use super::{Just, Nil, Vec3, VecSum};
+
+/// A multivector of the spacetime algebra Cl(1,3), with basis vectors
+/// γ0, γ1, γ2, γ3 squaring to +1, -1, -1, -1. As with `Multivector3`, every
+/// blade is either `Just` a coefficient or statically `Nil`.
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
+pub struct Spacetime<E, E0, E1, E2, E3, E01, E02, E03, E12, E13, E23, E012, E013, E023, E123, E0123> {
+    e: E,
+    e0: E0,
+    e1: E1,
+    e2: E2,
+    e3: E3,
+    e01: E01,
+    e02: E02,
+    e03: E03,
+    e12: E12,
+    e13: E13,
+    e23: E23,
+    e012: E012,
+    e013: E013,
+    e023: E023,
+    e123: E123,
+    e0123: E0123,
+}
+pub type FourVector<T> = Spacetime<
+    Nil,
+    Just<T>, Just<T>, Just<T>, Just<T>,
+    Nil, Nil, Nil, Nil, Nil, Nil,
+    Nil, Nil, Nil, Nil,
+    Nil,
+>;
+/// The electromagnetic field strength F = E + IB.
+pub type Faraday<T> = Spacetime<
+    Nil,
+    Nil, Nil, Nil, Nil,
+    Just<T>, Just<T>, Just<T>, Just<T>, Just<T>, Just<T>,
+    Nil, Nil, Nil, Nil,
+    Nil,
+>;
+/// The even subalgebra, which contains the Lorentz rotors.
+pub type Spinor<T> = Spacetime<
+    Just<T>,
+    Nil, Nil, Nil, Nil,
+    Just<T>, Just<T>, Just<T>, Just<T>, Just<T>, Just<T>,
+    Nil, Nil, Nil, Nil,
+    Just<T>,
+>;
+
+macro_rules! sta_e {
+    () => {
+        Spacetime<E, E0, E1, E2, E3, E01, E02, E03, E12, E13, E23, E012, E013, E023, E123, E0123>
+    }
+}
+
+/// Applies the sign of one entry of the product table to a coefficient.
+pub trait Signed<T> {
+    type Output;
+    fn apply(value: T) -> Self::Output;
+}
+pub struct Plus;
+pub struct Minus;
+impl<T> Signed<T> for Plus {
+    type Output = T;
+    fn apply(value: T) -> T {
+        value
+    }
+}
+impl<T: Neg> Signed<T> for Minus {
+    type Output = <T as Neg>::Output;
+    fn apply(value: T) -> Self::Output {
+        -value
+    }
+}
+
+// Each `Sg*` multiplies a single scaled basis blade on the left of a whole
+// spacetime multivector. The invocations below list, for every output blade,
+// the sign and the right hand blade it comes from.
+macro_rules! sta_blade {
+    ($name: ident; $($out: ident = $sign: ident $field: ident: $Field: ident),*) => {
+        pub struct $name<T>(T);
+        impl<T: Copy,
+            E: Copy, E0: Copy, E1: Copy, E2: Copy, E3: Copy,
+            E01: Copy, E02: Copy, E03: Copy, E12: Copy, E13: Copy, E23: Copy,
+            E012: Copy, E013: Copy, E023: Copy, E123: Copy, E0123: Copy>
+            Mul<&sta_e!()> for $name<T>
+        where
+            $($sign: Signed<T>, <$sign as Signed<T>>::Output: Mul<$Field>,)*
+        {
+            type Output = Spacetime<$(<<$sign as Signed<T>>::Output as Mul<$Field>>::Output),*>;
+            fn mul(self, rhs: &sta_e!()) -> Self::Output {
+                Spacetime {
+                    $($out: <$sign as Signed<T>>::apply(self.0) * rhs.$field),*
+                }
+            }
+        }
+    }
+}
+
+sta_blade!(Sg;
+    e = Plus e: E,
+    e0 = Plus e0: E0, e1 = Plus e1: E1, e2 = Plus e2: E2, e3 = Plus e3: E3,
+    e01 = Plus e01: E01, e02 = Plus e02: E02, e03 = Plus e03: E03,
+    e12 = Plus e12: E12, e13 = Plus e13: E13, e23 = Plus e23: E23,
+    e012 = Plus e012: E012, e013 = Plus e013: E013, e023 = Plus e023: E023, e123 = Plus e123: E123,
+    e0123 = Plus e0123: E0123
+);
+sta_blade!(Sg0;
+    e = Plus e0: E0,
+    e0 = Plus e: E, e1 = Plus e01: E01, e2 = Plus e02: E02, e3 = Plus e03: E03,
+    e01 = Plus e1: E1, e02 = Plus e2: E2, e03 = Plus e3: E3,
+    e12 = Plus e012: E012, e13 = Plus e013: E013, e23 = Plus e023: E023,
+    e012 = Plus e12: E12, e013 = Plus e13: E13, e023 = Plus e23: E23, e123 = Plus e0123: E0123,
+    e0123 = Plus e123: E123
+);
+sta_blade!(Sg1;
+    e = Minus e1: E1,
+    e0 = Plus e01: E01, e1 = Plus e: E, e2 = Minus e12: E12, e3 = Minus e13: E13,
+    e01 = Minus e0: E0, e02 = Plus e012: E012, e03 = Plus e013: E013,
+    e12 = Plus e2: E2, e13 = Plus e3: E3, e23 = Minus e123: E123,
+    e012 = Minus e02: E02, e013 = Minus e03: E03, e023 = Plus e0123: E0123, e123 = Plus e23: E23,
+    e0123 = Minus e023: E023
+);
+sta_blade!(Sg2;
+    e = Minus e2: E2,
+    e0 = Plus e02: E02, e1 = Plus e12: E12, e2 = Plus e: E, e3 = Minus e23: E23,
+    e01 = Minus e012: E012, e02 = Minus e0: E0, e03 = Plus e023: E023,
+    e12 = Minus e1: E1, e13 = Plus e123: E123, e23 = Plus e3: E3,
+    e012 = Plus e01: E01, e013 = Minus e0123: E0123, e023 = Minus e03: E03, e123 = Minus e13: E13,
+    e0123 = Plus e013: E013
+);
+sta_blade!(Sg3;
+    e = Minus e3: E3,
+    e0 = Plus e03: E03, e1 = Plus e13: E13, e2 = Plus e23: E23, e3 = Plus e: E,
+    e01 = Minus e013: E013, e02 = Minus e023: E023, e03 = Minus e0: E0,
+    e12 = Minus e123: E123, e13 = Minus e1: E1, e23 = Minus e2: E2,
+    e012 = Plus e0123: E0123, e013 = Plus e01: E01, e023 = Plus e02: E02, e123 = Plus e12: E12,
+    e0123 = Minus e012: E012
+);
+sta_blade!(Sg01;
+    e = Plus e01: E01,
+    e0 = Minus e1: E1, e1 = Minus e0: E0, e2 = Plus e012: E012, e3 = Plus e013: E013,
+    e01 = Plus e: E, e02 = Minus e12: E12, e03 = Minus e13: E13,
+    e12 = Minus e02: E02, e13 = Minus e03: E03, e23 = Plus e0123: E0123,
+    e012 = Plus e2: E2, e013 = Plus e3: E3, e023 = Minus e123: E123, e123 = Minus e023: E023,
+    e0123 = Plus e23: E23
+);
+sta_blade!(Sg02;
+    e = Plus e02: E02,
+    e0 = Minus e2: E2, e1 = Minus e012: E012, e2 = Minus e0: E0, e3 = Plus e023: E023,
+    e01 = Plus e12: E12, e02 = Plus e: E, e03 = Minus e23: E23,
+    e12 = Plus e01: E01, e13 = Minus e0123: E0123, e23 = Minus e03: E03,
+    e012 = Minus e1: E1, e013 = Plus e123: E123, e023 = Plus e3: E3, e123 = Plus e013: E013,
+    e0123 = Minus e13: E13
+);
+sta_blade!(Sg03;
+    e = Plus e03: E03,
+    e0 = Minus e3: E3, e1 = Minus e013: E013, e2 = Minus e023: E023, e3 = Minus e0: E0,
+    e01 = Plus e13: E13, e02 = Plus e23: E23, e03 = Plus e: E,
+    e12 = Plus e0123: E0123, e13 = Plus e01: E01, e23 = Plus e02: E02,
+    e012 = Minus e123: E123, e013 = Minus e1: E1, e023 = Minus e2: E2, e123 = Minus e012: E012,
+    e0123 = Plus e12: E12
+);
+sta_blade!(Sg12;
+    e = Minus e12: E12,
+    e0 = Minus e012: E012, e1 = Minus e2: E2, e2 = Plus e1: E1, e3 = Minus e123: E123,
+    e01 = Minus e02: E02, e02 = Plus e01: E01, e03 = Minus e0123: E0123,
+    e12 = Plus e: E, e13 = Minus e23: E23, e23 = Plus e13: E13,
+    e012 = Plus e0: E0, e013 = Minus e023: E023, e023 = Plus e013: E013, e123 = Plus e3: E3,
+    e0123 = Plus e03: E03
+);
+sta_blade!(Sg13;
+    e = Minus e13: E13,
+    e0 = Minus e013: E013, e1 = Minus e3: E3, e2 = Plus e123: E123, e3 = Plus e1: E1,
+    e01 = Minus e03: E03, e02 = Plus e0123: E0123, e03 = Plus e01: E01,
+    e12 = Plus e23: E23, e13 = Plus e: E, e23 = Minus e12: E12,
+    e012 = Plus e023: E023, e013 = Plus e0: E0, e023 = Minus e012: E012, e123 = Minus e2: E2,
+    e0123 = Minus e02: E02
+);
+sta_blade!(Sg23;
+    e = Minus e23: E23,
+    e0 = Minus e023: E023, e1 = Minus e123: E123, e2 = Minus e3: E3, e3 = Plus e2: E2,
+    e01 = Minus e0123: E0123, e02 = Minus e03: E03, e03 = Plus e02: E02,
+    e12 = Minus e13: E13, e13 = Plus e12: E12, e23 = Plus e: E,
+    e012 = Minus e013: E013, e013 = Plus e012: E012, e023 = Plus e0: E0, e123 = Plus e1: E1,
+    e0123 = Plus e01: E01
+);
+sta_blade!(Sg012;
+    e = Minus e012: E012,
+    e0 = Minus e12: E12, e1 = Minus e02: E02, e2 = Plus e01: E01, e3 = Minus e0123: E0123,
+    e01 = Minus e2: E2, e02 = Plus e1: E1, e03 = Minus e123: E123,
+    e12 = Plus e0: E0, e13 = Minus e023: E023, e23 = Plus e013: E013,
+    e012 = Plus e: E, e013 = Minus e23: E23, e023 = Plus e13: E13, e123 = Plus e03: E03,
+    e0123 = Plus e3: E3
+);
+sta_blade!(Sg013;
+    e = Minus e013: E013,
+    e0 = Minus e13: E13, e1 = Minus e03: E03, e2 = Plus e0123: E0123, e3 = Plus e01: E01,
+    e01 = Minus e3: E3, e02 = Plus e123: E123, e03 = Plus e1: E1,
+    e12 = Plus e023: E023, e13 = Plus e0: E0, e23 = Minus e012: E012,
+    e012 = Plus e23: E23, e013 = Plus e: E, e023 = Minus e12: E12, e123 = Minus e02: E02,
+    e0123 = Minus e2: E2
+);
+sta_blade!(Sg023;
+    e = Minus e023: E023,
+    e0 = Minus e23: E23, e1 = Minus e0123: E0123, e2 = Minus e03: E03, e3 = Plus e02: E02,
+    e01 = Minus e123: E123, e02 = Minus e3: E3, e03 = Plus e2: E2,
+    e12 = Minus e013: E013, e13 = Plus e012: E012, e23 = Plus e0: E0,
+    e012 = Minus e13: E13, e013 = Plus e12: E12, e023 = Plus e: E, e123 = Plus e01: E01,
+    e0123 = Plus e1: E1
+);
+sta_blade!(Sg123;
+    e = Plus e123: E123,
+    e0 = Minus e0123: E0123, e1 = Minus e23: E23, e2 = Plus e13: E13, e3 = Minus e12: E12,
+    e01 = Plus e023: E023, e02 = Minus e013: E013, e03 = Plus e012: E012,
+    e12 = Minus e3: E3, e13 = Plus e2: E2, e23 = Minus e1: E1,
+    e012 = Plus e03: E03, e013 = Minus e02: E02, e023 = Plus e01: E01, e123 = Plus e: E,
+    e0123 = Minus e0: E0
+);
+sta_blade!(Sg0123;
+    e = Minus e0123: E0123,
+    e0 = Plus e123: E123, e1 = Plus e023: E023, e2 = Minus e013: E013, e3 = Plus e012: E012,
+    e01 = Minus e23: E23, e02 = Plus e13: E13, e03 = Minus e12: E12,
+    e12 = Plus e03: E03, e13 = Minus e02: E02, e23 = Plus e01: E01,
+    e012 = Minus e3: E3, e013 = Plus e2: E2, e023 = Minus e1: E1, e123 = Minus e0: E0,
+    e0123 = Plus e: E
+);
+
+impl<E: Copy, E0: Copy, E1: Copy, E2: Copy, E3: Copy,
+    E01: Copy, E02: Copy, E03: Copy, E12: Copy, E13: Copy, E23: Copy,
+    E012: Copy, E013: Copy, E023: Copy, E123: Copy, E0123: Copy,
+    F: Copy, F0: Copy, F1: Copy, F2: Copy, F3: Copy,
+    F01: Copy, F02: Copy, F03: Copy, F12: Copy, F13: Copy, F23: Copy,
+    F012: Copy, F013: Copy, F023: Copy, F123: Copy, F0123: Copy>
+    Add<sta_e!()>
+    for Spacetime<F, F0, F1, F2, F3, F01, F02, F03, F12, F13, F23, F012, F013, F023, F123, F0123>
+where
+    F: Add<E>,
+    F0: Add<E0>,
+    F1: Add<E1>,
+    F2: Add<E2>,
+    F3: Add<E3>,
+    F01: Add<E01>,
+    F02: Add<E02>,
+    F03: Add<E03>,
+    F12: Add<E12>,
+    F13: Add<E13>,
+    F23: Add<E23>,
+    F012: Add<E012>,
+    F013: Add<E013>,
+    F023: Add<E023>,
+    F123: Add<E123>,
+    F0123: Add<E0123>,
+{
+    type Output = Spacetime<
+        <F as Add<E>>::Output,
+        <F0 as Add<E0>>::Output,
+        <F1 as Add<E1>>::Output,
+        <F2 as Add<E2>>::Output,
+        <F3 as Add<E3>>::Output,
+        <F01 as Add<E01>>::Output,
+        <F02 as Add<E02>>::Output,
+        <F03 as Add<E03>>::Output,
+        <F12 as Add<E12>>::Output,
+        <F13 as Add<E13>>::Output,
+        <F23 as Add<E23>>::Output,
+        <F012 as Add<E012>>::Output,
+        <F013 as Add<E013>>::Output,
+        <F023 as Add<E023>>::Output,
+        <F123 as Add<E123>>::Output,
+        <F0123 as Add<E0123>>::Output,
+    >;
+    fn add(self, rhs: sta_e!()) -> Self::Output {
+        Spacetime {
+            e: self.e + rhs.e,
+            e0: self.e0 + rhs.e0,
+            e1: self.e1 + rhs.e1,
+            e2: self.e2 + rhs.e2,
+            e3: self.e3 + rhs.e3,
+            e01: self.e01 + rhs.e01,
+            e02: self.e02 + rhs.e02,
+            e03: self.e03 + rhs.e03,
+            e12: self.e12 + rhs.e12,
+            e13: self.e13 + rhs.e13,
+            e23: self.e23 + rhs.e23,
+            e012: self.e012 + rhs.e012,
+            e013: self.e013 + rhs.e013,
+            e023: self.e023 + rhs.e023,
+            e123: self.e123 + rhs.e123,
+            e0123: self.e0123 + rhs.e0123,
+        }
+    }
+}
+
+// `VecSum` only goes up to eight terms, so the sixteen partial products are
+// summed in two halves.
+macro_rules! sta_lower {
+    ($a: lifetime) => {
+        (
+            <Sg<F> as Mul<&$a sta_e!()>>::Output,
+            <Sg0<F0> as Mul<&$a sta_e!()>>::Output,
+            <Sg1<F1> as Mul<&$a sta_e!()>>::Output,
+            <Sg2<F2> as Mul<&$a sta_e!()>>::Output,
+            <Sg3<F3> as Mul<&$a sta_e!()>>::Output,
+            <Sg01<F01> as Mul<&$a sta_e!()>>::Output,
+            <Sg02<F02> as Mul<&$a sta_e!()>>::Output,
+            <Sg03<F03> as Mul<&$a sta_e!()>>::Output,
+        )
+    }
+}
+macro_rules! sta_upper {
+    ($a: lifetime) => {
+        (
+            <Sg12<F12> as Mul<&$a sta_e!()>>::Output,
+            <Sg13<F13> as Mul<&$a sta_e!()>>::Output,
+            <Sg23<F23> as Mul<&$a sta_e!()>>::Output,
+            <Sg012<F012> as Mul<&$a sta_e!()>>::Output,
+            <Sg013<F013> as Mul<&$a sta_e!()>>::Output,
+            <Sg023<F023> as Mul<&$a sta_e!()>>::Output,
+            <Sg123<F123> as Mul<&$a sta_e!()>>::Output,
+            <Sg0123<F0123> as Mul<&$a sta_e!()>>::Output,
+        )
+    }
+}
+
+impl<'a, E: Copy, E0: Copy, E1: Copy, E2: Copy, E3: Copy,
+    E01: Copy, E02: Copy, E03: Copy, E12: Copy, E13: Copy, E23: Copy,
+    E012: Copy, E013: Copy, E023: Copy, E123: Copy, E0123: Copy,
+    F: Copy, F0: Copy, F1: Copy, F2: Copy, F3: Copy,
+    F01: Copy, F02: Copy, F03: Copy, F12: Copy, F13: Copy, F23: Copy,
+    F012: Copy, F013: Copy, F023: Copy, F123: Copy, F0123: Copy>
+    Mul<&'a sta_e!()>
+    for &Spacetime<F, F0, F1, F2, F3, F01, F02, F03, F12, F13, F23, F012, F013, F023, F123, F0123>
+where
+    Sg<F>: Mul<&'a sta_e!()>,
+    Sg0<F0>: Mul<&'a sta_e!()>,
+    Sg1<F1>: Mul<&'a sta_e!()>,
+    Sg2<F2>: Mul<&'a sta_e!()>,
+    Sg3<F3>: Mul<&'a sta_e!()>,
+    Sg01<F01>: Mul<&'a sta_e!()>,
+    Sg02<F02>: Mul<&'a sta_e!()>,
+    Sg03<F03>: Mul<&'a sta_e!()>,
+    Sg12<F12>: Mul<&'a sta_e!()>,
+    Sg13<F13>: Mul<&'a sta_e!()>,
+    Sg23<F23>: Mul<&'a sta_e!()>,
+    Sg012<F012>: Mul<&'a sta_e!()>,
+    Sg013<F013>: Mul<&'a sta_e!()>,
+    Sg023<F023>: Mul<&'a sta_e!()>,
+    Sg123<F123>: Mul<&'a sta_e!()>,
+    Sg0123<F0123>: Mul<&'a sta_e!()>,
+    sta_lower!('a): VecSum,
+    sta_upper!('a): VecSum,
+    <sta_lower!('a) as VecSum>::Output: Add<<sta_upper!('a) as VecSum>::Output>,
+{
+    type Output = <<sta_lower!('a) as VecSum>::Output as Add<<sta_upper!('a) as VecSum>::Output>>::Output;
+    fn mul(self, rhs: &'a sta_e!()) -> Self::Output {
+        let lower = (
+            Sg(self.e) * rhs,
+            Sg0(self.e0) * rhs,
+            Sg1(self.e1) * rhs,
+            Sg2(self.e2) * rhs,
+            Sg3(self.e3) * rhs,
+            Sg01(self.e01) * rhs,
+            Sg02(self.e02) * rhs,
+            Sg03(self.e03) * rhs,
+        ).value();
+        let upper = (
+            Sg12(self.e12) * rhs,
+            Sg13(self.e13) * rhs,
+            Sg23(self.e23) * rhs,
+            Sg012(self.e012) * rhs,
+            Sg013(self.e013) * rhs,
+            Sg023(self.e023) * rhs,
+            Sg123(self.e123) * rhs,
+            Sg0123(self.e0123) * rhs,
+        ).value();
+        lower + upper
+    }
+}
+
+impl<E, E0, E1, E2, E3, E01: Neg, E02: Neg, E03: Neg, E12: Neg, E13: Neg, E23: Neg,
+    E012: Neg, E013: Neg, E023: Neg, E123: Neg, E0123> sta_e!()
+{
+    /// Reverses the order of the basis vectors in every blade, which flips
+    /// the sign of the bivector and trivector parts.
+    #[allow(clippy::type_complexity)]
+    pub fn reverse(self) -> Spacetime<
+        E, E0, E1, E2, E3,
+        <E01 as Neg>::Output, <E02 as Neg>::Output, <E03 as Neg>::Output,
+        <E12 as Neg>::Output, <E13 as Neg>::Output, <E23 as Neg>::Output,
+        <E012 as Neg>::Output, <E013 as Neg>::Output, <E023 as Neg>::Output, <E123 as Neg>::Output,
+        E0123,
+    > {
+        Spacetime {
+            e: self.e,
+            e0: self.e0,
+            e1: self.e1,
+            e2: self.e2,
+            e3: self.e3,
+            e01: -self.e01,
+            e02: -self.e02,
+            e03: -self.e03,
+            e12: -self.e12,
+            e13: -self.e13,
+            e23: -self.e23,
+            e012: -self.e012,
+            e013: -self.e013,
+            e023: -self.e023,
+            e123: -self.e123,
+            e0123: self.e0123,
+        }
+    }
+}
+
+impl<T> FourVector<T> {
+    /// The four-vector `t γ0 + x γ1 + y γ2 + z γ3`.
+    pub fn new(t: T, x: T, y: T, z: T) -> Self {
+        Spacetime {
+            e: Nil(),
+            e0: Just(t), e1: Just(x), e2: Just(y), e3: Just(z),
+            e01: Nil(), e02: Nil(), e03: Nil(), e12: Nil(), e13: Nil(), e23: Nil(),
+            e012: Nil(), e013: Nil(), e023: Nil(), e123: Nil(),
+            e0123: Nil(),
+        }
+    }
+}
+
+impl<T: Real> Spinor<T> {
+    fn even(e: T, e01: T, e02: T, e03: T, e12: T, e13: T, e23: T) -> Self {
+        Spacetime {
+            e: Just(e),
+            e0: Nil(), e1: Nil(), e2: Nil(), e3: Nil(),
+            e01: Just(e01), e02: Just(e02), e03: Just(e03),
+            e12: Just(e12), e13: Just(e13), e23: Just(e23),
+            e012: Nil(), e013: Nil(), e023: Nil(), e123: Nil(),
+            e0123: Just(T::zero()),
+        }
+    }
+
+    /// The rotor for a pure boost with the given rapidity along a unit
+    /// spatial direction, so that `γ0` is carried to the four-velocity
+    /// `cosh(φ) γ0 + sinh(φ) n`.
+    pub fn boost(rapidity: T, direction: &Vec3<T>) -> Self {
+        let half = rapidity / (T::one() + T::one());
+        let (c, s) = (half.cosh(), half.sinh());
+        let (x, y, z) = (direction.e1.0, direction.e2.0, direction.e3.0);
+        Spinor::even(c, -s * x, -s * y, -s * z, T::zero(), T::zero(), T::zero())
+    }
+
+    /// The rotor for a right handed spatial rotation by `angle` about a unit
+    /// axis.
+    pub fn rotation(angle: T, axis: &Vec3<T>) -> Self {
+        let half = angle / (T::one() + T::one());
+        let (c, s) = (half.cos(), half.sin());
+        let (x, y, z) = (axis.e1.0, axis.e2.0, axis.e3.0);
+        Spinor::even(c, T::zero(), T::zero(), T::zero(), s * z, -s * y, s * x)
+    }
+
+    /// Applies the Lorentz transformation `R x ~R` to a four-vector.
+    pub fn transform(&self, x: &FourVector<T>) -> FourVector<T> {
+        let moved = &(self * x) * &self.clone().reverse();
+        FourVector::new(moved.e0.0, moved.e1.0, moved.e2.0, moved.e3.0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn close(a: &FourVector<f64>, b: &FourVector<f64>) -> bool {
+        [(a.e0, b.e0), (a.e1, b.e1), (a.e2, b.e2), (a.e3, b.e3)]
+            .iter()
+            .all(|(x, y)| (x.0 - y.0).abs() < 1e-12)
+    }
+
+    #[test]
+    fn signature() {
+        let t = FourVector::new(1.0, 0.0, 0.0, 0.0);
+        let x = FourVector::new(0.0, 1.0, 0.0, 0.0);
+        assert_eq!((&t * &t).e, Just(1.0));
+        assert_eq!((&x * &x).e, Just(-1.0));
+        assert_eq!((&t * &x).e01, Just(1.0));
+        assert_eq!((&x * &t).e01, Just(-1.0));
+
+        let v = FourVector::new(3.0, 1.0, -2.0, 0.5);
+        assert_eq!((&v * &v).e, Just(9.0 - 1.0 - 4.0 - 0.25));
+
+        let pseudoscalar = &(&(&t * &x) * &FourVector::new(0.0, 0.0, 1.0, 0.0)) * &FourVector::new(0.0, 0.0, 0.0, 1.0);
+        assert_eq!(pseudoscalar.e0123, Just(1.0));
+        assert_eq!((&pseudoscalar * &pseudoscalar).e, Just(-1.0));
+    }
+
+    #[test]
+    fn rotation_is_right_handed() {
+        let rotor = Spinor::rotation(std::f64::consts::FRAC_PI_2, &Vec3::new(0.0, 0.0, 1.0));
+        let rotated = rotor.transform(&FourVector::new(2.0, 1.0, 0.0, 0.0));
+        assert!(close(&rotated, &FourVector::new(2.0, 0.0, 1.0, 0.0)));
+    }
+
+    #[test]
+    fn boost_preserves_interval() {
+        let rotor = Spinor::boost(0.8, &Vec3::new(0.6, 0.0, 0.8));
+        let event = FourVector::new(3.0f64, 1.0, -2.0, 0.5);
+        let boosted = rotor.transform(&event);
+        assert!(((&boosted * &boosted).e.0 - (&event * &event).e.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn relativistic_velocity_addition() {
+        let (u, v) = (0.6f64, 0.7f64);
+        let x = Vec3::new(1.0, 0.0, 0.0);
+        let first = Spinor::boost(u.atanh(), &x);
+        let second = Spinor::boost(v.atanh(), &x);
+        let rest = FourVector::new(1.0, 0.0, 0.0, 0.0);
+
+        let moving = (&second * &first).transform(&rest);
+        let w = moving.e1.0 / moving.e0.0;
+        assert!((w - (u + v) / (1.0 + u * v)).abs() < 1e-12);
+
+        let once = second.transform(&first.transform(&rest));
+        assert!(close(&moving, &once));
+
+        // A body moving at u along x in a frame that itself moves at v along y.
+        let perpendicular = Spinor::boost(v.atanh(), &Vec3::new(0.0, 1.0, 0.0));
+        let moving = (&perpendicular * &first).transform(&rest);
+        let (wx, wy) = (moving.e1.0 / moving.e0.0, moving.e2.0 / moving.e0.0);
+        assert!((wx - u * (1.0 - v * v).sqrt()).abs() < 1e-12);
+        assert!((wy - v).abs() < 1e-12);
+    }
+}